@@ -1,18 +1,35 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Decimal,
+    attr, to_binary, Addr, Api, Binary, CosmosMsg, Decimal,
     Deps, DepsMut, Env, MessageInfo, Order, Coin,
     Response, StdResult, Uint128, Uint64, WasmMsg, BankMsg,
+    Event, Reply, Storage, SubMsg, SubMsgResult, Timestamp,
 };
+use cw2::{get_contract_version, set_contract_version};
+use cw_utils::{Duration, Expiration};
 use cw_storage_plus::Bound;
 use goblin_governance::{
-    msg::{InstantiateMsg, ExecuteMsg},
-    config::{Config, UpdateConfig},
-    state::{CONFIG, PROPOSAL_COUNT, PROPOSALS, validate_funds},
-    err::ContractError, proposal::{Proposal, ProposalStatus, ProposalVoteOption},
-    query::{QueryMsg, ProposalListResponse, ProposalVotesResponse},
+    msg::{InstantiateMsg, ExecuteMsg, MigrateMsg},
+    config::{Config, DepositDestination, UpdateConfig},
+    state::{
+        CONFIG, DEPOSITS, EXECUTION_ERRORS, KEEPER_POOL, OPTION_VOTERS, PROPOSAL_COUNT, VOTES,
+        proposals, save_vote, validate_funds,
+    },
+    err::ContractError, proposal::{Proposal, ProposalKind, ProposalStatus, ProposalVote, ProposalVoteOption},
+    query::{
+        QueryMsg, OrderBy, ProposalExecutionResponse, ProposalListResponse,
+        ProposalVotesResponse,
+    },
 };
 use std::str::FromStr;
 use goblin_staking::query::QueryMsg as StakingQueryMsg;
+use goblin_staking::config::PenaltyDestination;
+use crate::migration::{migrate_config, migrate_proposals};
+
+// Version info for migration
+const CONTRACT_NAME: &str = "crates.io:goblin-governance";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 
 // Default pagination constants
@@ -38,17 +55,51 @@ pub fn instantiate(
         proposal_effective_delay: msg.proposal_effective_delay,
         proposal_expiration_period: msg.proposal_expiration_period,
         proposal_required_deposit: msg.proposal_required_deposit,
+        proposal_deposit_period: msg.proposal_deposit_period,
+        proposal_min_initial_deposit: Decimal::from_str(&msg.proposal_min_initial_deposit)?,
         proposal_required_quorum: Decimal::from_str(&msg.proposal_required_quorum)?,
         proposal_required_threshold: Decimal::from_str(&msg.proposal_required_threshold)?,
+        proposal_veto_threshold: Decimal::from_str(&msg.proposal_veto_threshold)?,
+        expedited_voting_period: msg.expedited_voting_period,
+        expedited_required_deposit: msg.expedited_required_deposit,
+        expedited_required_quorum: Decimal::from_str(&msg.expedited_required_quorum)?,
+        expedited_required_threshold: Decimal::from_str(&msg.expedited_required_threshold)?,
+        no_quorum_deposit_destination: validate_deposit_destination(
+            deps.api,
+            msg.no_quorum_deposit_destination,
+        )?,
+        veto_deposit_destination: validate_deposit_destination(
+            deps.api,
+            msg.veto_deposit_destination,
+        )?,
+        guardian: msg
+            .guardian
+            .map(|guardian| deps.api.addr_validate(&guardian))
+            .transpose()?,
+        keeper_reward: msg.keeper_reward,
     };
 
     config.validate()?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     CONFIG.save(deps.storage, &config)?;
     PROPOSAL_COUNT.save(deps.storage, &Uint64::zero())?;
 
     Ok(Response::default())
 }
 
+/// Validates the treasury address of a deposit destination.
+fn validate_deposit_destination(
+    api: &dyn Api,
+    destination: DepositDestination,
+) -> StdResult<DepositDestination> {
+    match destination {
+        DepositDestination::Treasury { address } => Ok(DepositDestination::Treasury {
+            address: api.addr_validate(address.as_str())?,
+        }),
+        destination => Ok(destination),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -61,9 +112,16 @@ pub fn execute(
             title,
             description,
             link,
-            messages,
-        } => submit_proposal(deps, env, info, title, description, link, messages),
-        ExecuteMsg::CastVote { proposal_id, vote } => cast_vote(deps, env, info, proposal_id, vote),
+            kind,
+            expedited,
+        } => submit_proposal(deps, env, info, title, description, link, kind, expedited),
+        ExecuteMsg::CastVote { proposal_id, vote } => {
+            cast_vote(deps, env, info, proposal_id, vec![(vote, Decimal::one())])
+        }
+        ExecuteMsg::CastWeightedVote {
+            proposal_id,
+            options,
+        } => cast_vote(deps, env, info, proposal_id, options),
         ExecuteMsg::EndProposal { proposal_id } => end_proposal(deps, env, proposal_id),
         ExecuteMsg::ExecuteProposal { proposal_id } => execute_proposal(deps, env, proposal_id),
         ExecuteMsg::CheckMessages { messages } => check_messages(env, messages),
@@ -71,7 +129,14 @@ pub fn execute(
         ExecuteMsg::RemoveCompletedProposal { proposal_id } => {
             remove_completed_proposal(deps, env, proposal_id)
         }
-        ExecuteMsg::UpdateConfig(config) => update_config(deps, env, info, config),
+        ExecuteMsg::CancelProposal { proposal_id } => {
+            cancel_proposal(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::VetoProposal { proposal_id } => veto_proposal(deps, env, info, proposal_id),
+        ExecuteMsg::AddDeposit { proposal_id } => add_deposit(deps, env, info, proposal_id),
+        ExecuteMsg::Crank { limit } => crank(deps, env, info, limit),
+        ExecuteMsg::FundKeeperPool {} => fund_keeper_pool(deps, info),
+        ExecuteMsg::UpdateConfig(config) => update_config(deps, env, info, *config),
     }
 }
 
@@ -83,14 +148,16 @@ pub fn submit_proposal(
     title: String,
     description: String,
     link: Option<String>,
-    messages: Option<Vec<CosmosMsg>>,
+    kind: ProposalKind,
+    expedited: bool,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    // check the deposit
+    // check the deposit, the rest can be added during the deposit period
     let deposit_coin = validate_funds(deps.storage, info.funds)?;
-    let deposit_amount = deposit_coin.amount; 
-    if deposit_amount < config.proposal_required_deposit {
+    let deposit_amount = deposit_coin.amount;
+    let required_deposit = config.proposal_params(expedited).required_deposit;
+    if deposit_amount < required_deposit * config.proposal_min_initial_deposit {
         return Err(ContractError::InsufficientDeposit {});
     }
 
@@ -99,53 +166,174 @@ pub fn submit_proposal(
         Ok(c.checked_add(Uint64::new(1))?)
     })?;
 
-    let proposal = Proposal {
+    let mut proposal = Proposal {
         proposal_id: count,
         submitter: info.sender.clone(),
-        status: ProposalStatus::Active,
+        status: ProposalStatus::Deposit,
+        expedited,
         yes_power: Uint128::zero(),
         no_power: Uint128::zero(),
-        yes_voters: Vec::new(),
-        no_voters: Vec::new(),
-        start_block: env.block.height,
-        start_time: env.block.time.seconds(),
-        end_block: env.block.height + config.proposal_voting_period,
-        delayed_end_block: env.block.height
-            + config.proposal_voting_period
-            + config.proposal_effective_delay,
-        expiration_block: env.block.height
-            + config.proposal_voting_period
-            + config.proposal_effective_delay
-            + config.proposal_expiration_period,
+        abstain_power: Uint128::zero(),
+        veto_power: Uint128::zero(),
+        deposit_end: config.proposal_deposit_period.after(&env.block),
+        start_block: 0,
+        start_time: 0,
+        snapshot_height: 0,
+        total_voting_power: Uint128::zero(),
+        voting_end: Expiration::Never {},
+        delay_end: Expiration::Never {},
+        expiration: Expiration::Never {},
         title,
         description,
         link,
-        messages,
+        kind,
         deposit_amount,
+        deposit_destination: None,
     };
 
     proposal.validate()?;
-    PROPOSALS.save(deps.storage, count.u64(), &proposal)?;
+    validate_proposal_kind(deps.as_ref(), &config, &mut proposal.kind)?;
+    if deposit_amount >= required_deposit {
+        start_voting(deps.as_ref(), &env, &config, &mut proposal)?;
+    }
+    proposals().save(deps.storage, count.u64(), &proposal)?;
+    DEPOSITS.save(deps.storage, (count.u64(), &info.sender), &deposit_amount)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "submit_proposal"),
         attr("submitter", info.sender),
         attr("proposal_id", count),
-        attr(
-            "proposal_end_height",
-            (env.block.height + config.proposal_voting_period).to_string(),
-        ),
+        attr("status", proposal.status.to_string()),
+        attr("deposit_end", proposal.deposit_end.to_string()),
+        attr("voting_end", proposal.voting_end.to_string()),
     ]))
 }
 
+/// Checks a proposal kind against the current state and normalizes its addresses.
+fn validate_proposal_kind(
+    deps: Deps,
+    config: &Config,
+    kind: &mut ProposalKind,
+) -> Result<(), ContractError> {
+    kind.validate()?;
+
+    match kind {
+        ProposalKind::UpdateGovConfig(update) => {
+            // The updated config has to be valid at submission already
+            apply_config_update(deps.api, config.clone(), *update.clone())?;
+        }
+        ProposalKind::MigrateContract { contract_addr, .. } => {
+            *contract_addr = deps.api.addr_validate(contract_addr)?.into_string();
+        }
+        ProposalKind::TreasurySpend { recipient, .. } => {
+            *recipient = deps.api.addr_validate(recipient)?.into_string();
+        }
+        ProposalKind::UpdateStakingConfig(update) => {
+            // Settings depending on the current staking config are checked on execution
+            update.validate()?;
+            if let Some(PenaltyDestination::Address { address }) = &update.penalty_destination {
+                deps.api.addr_validate(address.as_str())?;
+            }
+        }
+        ProposalKind::Text | ProposalKind::Custom { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// Adds the attached funds to the deposit of a proposal in its deposit period.
+pub fn add_deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
+
+    if proposal.status != ProposalStatus::Deposit {
+        return Err(ContractError::ProposalNotInDepositPeriod {});
+    }
+
+    if proposal.deposit_end.is_expired(&env.block) {
+        return Err(ContractError::DepositPeriodEnded {});
+    }
+
+    let amount = validate_funds(deps.storage, info.funds)?.amount;
+    DEPOSITS.update(deps.storage, (proposal_id, &info.sender), |deposit| -> StdResult<_> {
+        Ok(deposit.unwrap_or_default().checked_add(amount)?)
+    })?;
+    proposal.deposit_amount = proposal.deposit_amount.checked_add(amount)?;
+
+    if proposal.deposit_amount >= config.proposal_params(proposal.expedited).required_deposit {
+        start_voting(deps.as_ref(), &env, &config, &mut proposal)?;
+    }
+    proposals().save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_deposit"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("depositor", info.sender),
+        attr("amount", amount),
+        attr("total_deposit", proposal.deposit_amount),
+        attr("status", proposal.status.to_string()),
+    ]))
+}
+
+/// Opens the voting of a proposal that reached the required deposit.
+fn start_voting(deps: Deps, env: &Env, config: &Config, proposal: &mut Proposal) -> StdResult<()> {
+    // Stakes are snapshotted at the start of a block, so the voting power is
+    // taken from the previous block to ignore stakes added in this one
+    proposal.snapshot_height = env.block.height.saturating_sub(1);
+    proposal.total_voting_power = calc_total_voting_power_at(deps, proposal.snapshot_height)?;
+
+    proposal.status = ProposalStatus::Active;
+    proposal.start_block = env.block.height;
+    proposal.start_time = env.block.time.seconds();
+    set_voting_period(config, proposal)
+}
+
+/// Sets the voting, delay and expiration deadlines of a proposal from its start.
+fn set_voting_period(config: &Config, proposal: &mut Proposal) -> StdResult<()> {
+    let voting_period = config.proposal_params(proposal.expedited).voting_period;
+    let start = match voting_period {
+        Duration::Height(_) => Expiration::AtHeight(proposal.start_block),
+        Duration::Time(_) => Expiration::AtTime(Timestamp::from_seconds(proposal.start_time)),
+    };
+    proposal.voting_end = (start + voting_period)?;
+    proposal.delay_end = (proposal.voting_end + config.proposal_effective_delay)?;
+    proposal.expiration = (proposal.delay_end + config.proposal_expiration_period)?;
+    Ok(())
+}
+
+/// Returns the deposits of a proposal to every depositor in proportion to what they added.
+fn refund_deposits(deps: Deps, proposal_id: u64, denom: &str) -> StdResult<Vec<BankMsg>> {
+    DEPOSITS
+        .prefix(proposal_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (depositor, amount) = item?;
+            Ok(BankMsg::Send {
+                to_address: depositor.into_string(),
+                amount: vec![Coin {
+                    denom: denom.to_string(),
+                    amount,
+                }],
+            })
+        })
+        .collect()
+}
+
 pub fn cast_vote(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     proposal_id: u64,
-    vote_option: ProposalVoteOption,
+    options: Vec<(ProposalVoteOption, Decimal)>,
 ) -> Result<Response, ContractError> {
-    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    validate_vote_weights(&options)?;
+
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
 
     if proposal.status != ProposalStatus::Active {
         return Err(ContractError::ProposalNotActive {});
@@ -156,131 +344,363 @@ pub fn cast_vote(
         return Err(ContractError::Unauthorized {});
     }
 
-    if env.block.height > proposal.end_block {
+    if proposal.voting_end.is_expired(&env.block) {
         return Err(ContractError::VotingPeriodEnded {});
     }
 
-    if proposal.yes_voters.contains(&info.sender) || proposal.no_voters.contains(&info.sender) {
-        return Err(ContractError::UserAlreadyVoted {});
-    }
-
     let voting_power = calc_voting_power(deps.as_ref(), info.sender.to_string(), &proposal)?;
 
     if voting_power.is_zero() {
         return Err(ContractError::NoVotingPower {});
     }
 
-    match vote_option {
-        ProposalVoteOption::For => {
-            proposal.yes_power = proposal.yes_power.checked_add(voting_power)?;
-            proposal.yes_voters.push(info.sender.clone());
-        }
-        ProposalVoteOption::Against => {
-            proposal.no_power = proposal.no_power.checked_add(voting_power)?;
-            proposal.no_voters.push(info.sender.clone());
-        }
+    // Voters may change their mind while voting is open,
+    // the previous vote is taken back before counting the new one
+    let previous_vote = VOTES.may_load(deps.storage, (proposal_id, &info.sender))?;
+    if let Some(previous_vote) = &previous_vote {
+        proposal.remove_vote(previous_vote)?;
+    }
+
+    let vote = ProposalVote {
+        options,
+        power: voting_power,
     };
+    proposal.add_vote(&vote)?;
+    save_vote(deps.storage, proposal_id, &info.sender, &vote, previous_vote.as_ref())?;
 
-    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    proposals().save(deps.storage, proposal_id, &proposal)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "cast_vote"),
         attr("proposal_id", proposal_id.to_string()),
         attr("voter", &info.sender),
-        attr("vote", vote_option.to_string()),
+        attr("vote", vote.to_string()),
+        attr(
+            "previous_vote",
+            previous_vote.map_or_else(|| "none".to_string(), |vote| vote.to_string()),
+        ),
         attr("voting_power", voting_power),
     ]))
 }
 
+/// Checks that every option is voted at most once with a positive weight
+/// and that the weights sum up to one.
+fn validate_vote_weights(options: &[(ProposalVoteOption, Decimal)]) -> Result<(), ContractError> {
+    let mut total = Decimal::zero();
+    for (i, (option, weight)) in options.iter().enumerate() {
+        if weight.is_zero() || options[..i].iter().any(|(voted, _)| voted == option) {
+            return Err(ContractError::InvalidVoteWeights {});
+        }
+        total = total.checked_add(*weight)?;
+    }
+
+    if total != Decimal::one() {
+        return Err(ContractError::InvalidVoteWeights {});
+    }
+
+    Ok(())
+}
+
 pub fn end_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Response, ContractError> {
-    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
+
+    if proposal.status == ProposalStatus::Deposit {
+        return end_deposit_period(deps, env, proposal);
+    }
 
     if proposal.status != ProposalStatus::Active {
         return Err(ContractError::ProposalNotActive {});
     }
 
-    if env.block.height <= proposal.end_block {
+    if !proposal.voting_end.is_expired(&env.block) {
         return Err(ContractError::VotingPeriodNotEnded {});
     }
 
     let config = CONFIG.load(deps.storage)?;
 
+    // Vetoes count against the proposal, abstentions only towards the quorum
     let for_votes = proposal.yes_power;
-    let against_votes = proposal.no_power;
-    let total_votes = for_votes + against_votes;
+    let against_votes = proposal.no_power + proposal.veto_power;
+    let total_votes = for_votes + against_votes + proposal.abstain_power;
 
-    let total_voting_power = calc_total_voting_power_at(deps.as_ref(), &proposal)?;
+    let total_voting_power = proposal.total_voting_power;
 
     let mut proposal_quorum: Decimal = Decimal::zero();
     let mut proposal_threshold: Decimal = Decimal::zero();
+    let mut proposal_veto: Decimal = Decimal::zero();
 
     if !total_voting_power.is_zero() {
         proposal_quorum = Decimal::from_ratio(total_votes, total_voting_power);
     }
 
+    if !(for_votes + against_votes).is_zero() {
+        proposal_threshold = Decimal::from_ratio(for_votes, for_votes + against_votes);
+    }
+
     if !total_votes.is_zero() {
-        proposal_threshold = Decimal::from_ratio(for_votes, total_votes);
+        proposal_veto = Decimal::from_ratio(proposal.veto_power, total_votes);
+    }
+
+    let params = config.proposal_params(proposal.expedited);
+    let quorum_reached = proposal_quorum >= params.required_quorum;
+    let vetoed = quorum_reached && proposal_veto > config.proposal_veto_threshold;
+    let passed = quorum_reached && !vetoed && proposal_threshold > params.required_threshold;
+
+    // An expedited proposal that did not pass keeps its votes and
+    // continues as a regular proposal unless it was vetoed
+    if proposal.expedited && !passed && !vetoed {
+        proposal.expedited = false;
+        set_voting_period(&config, &mut proposal)?;
+        proposals().save(deps.storage, proposal_id, &proposal)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "end_proposal"),
+            attr("proposal_id", proposal_id.to_string()),
+            attr("proposal_result", "expedited_fallback"),
+            attr("voting_end", proposal.voting_end.to_string()),
+        ]));
     }
 
     // Determine the proposal result
-    proposal.status = if proposal_quorum >= config.proposal_required_quorum
-        && proposal_threshold > config.proposal_required_threshold
-    {
+    proposal.status = if passed {
         ProposalStatus::Passed
     } else {
         ProposalStatus::Rejected
     };
 
-    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    // Deposits of proposals without quorum or with a veto are handled as configured
+    let destination = if vetoed {
+        config.veto_deposit_destination
+    } else if !quorum_reached {
+        config.no_quorum_deposit_destination
+    } else {
+        DepositDestination::Refund
+    };
+
+    let deposit = vec![Coin {
+        denom: config.gov_token_denom.clone(),
+        amount: proposal.deposit_amount,
+    }];
+    let deposit_msgs = match &destination {
+        DepositDestination::Refund => {
+            refund_deposits(deps.as_ref(), proposal_id, &config.gov_token_denom)?
+        }
+        DepositDestination::Burn => vec![BankMsg::Burn { amount: deposit }],
+        DepositDestination::Treasury { address } => vec![BankMsg::Send {
+            to_address: address.to_string(),
+            amount: deposit,
+        }],
+    };
+
+    let mut deposit_event = Event::new("proposal_deposit").add_attributes(vec![
+        attr("proposal_id", proposal_id.to_string()),
+        attr("destination", destination.to_string()),
+        attr("amount", proposal.deposit_amount),
+    ]);
+    if let DepositDestination::Treasury { address } = &destination {
+        deposit_event = deposit_event.add_attribute("treasury", address);
+    }
 
     let response = Response::new()
         .add_attributes(vec![
             attr("action", "end_proposal"),
             attr("proposal_id", proposal_id.to_string()),
             attr("proposal_result", proposal.status.to_string()),
+            attr("deposit", destination.to_string()),
         ])
-        .add_message(BankMsg::Send{
-            to_address: proposal.submitter.into_string(),
-            amount: vec![Coin{ 
-                denom: config.gov_token_denom, 
-                amount: proposal.deposit_amount,
-            }],
-        });
+        .add_event(deposit_event)
+        .add_messages(deposit_msgs);
+
+    proposal.deposit_destination = Some(destination);
+    proposals().save(deps.storage, proposal_id, &proposal)?;
 
     Ok(response)
 }
 
+/// Expires a proposal that did not collect the required deposit in time and refunds its depositors.
+fn end_deposit_period(
+    deps: DepsMut,
+    env: Env,
+    mut proposal: Proposal,
+) -> Result<Response, ContractError> {
+    if !proposal.deposit_end.is_expired(&env.block) {
+        return Err(ContractError::DepositPeriodNotEnded {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let proposal_id = proposal.proposal_id.u64();
+
+    proposal.status = ProposalStatus::Expired;
+    proposal.deposit_destination = Some(DepositDestination::Refund);
+    proposals().save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "end_proposal"),
+            attr("proposal_id", proposal_id.to_string()),
+            attr("proposal_result", proposal.status.to_string()),
+            attr("deposit", DepositDestination::Refund.to_string()),
+        ])
+        .add_messages(refund_deposits(deps.as_ref(), proposal_id, &config.gov_token_denom)?))
+}
+
 /// Executes a successful proposal by id.
 pub fn execute_proposal(
     deps: DepsMut,
     env: Env,
     proposal_id: u64,
 ) -> Result<Response, ContractError> {
-    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
 
     if proposal.status != ProposalStatus::Passed {
         return Err(ContractError::ProposalNotPassed {});
     }
 
-    if env.block.height < proposal.delayed_end_block {
+    if !proposal.delay_end.is_expired(&env.block) {
         return Err(ContractError::ProposalDelayNotEnded {});
     }
 
-    if env.block.height > proposal.expiration_block {
+    if proposal.expiration.is_expired(&env.block) {
         return Err(ContractError::ExecuteProposalExpired {});
     }
 
-    let messages;
-    
-    // mark as executed and unwrap proposal messages
-    proposal.status = ProposalStatus::Executed;
-    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
-    messages = proposal.messages.unwrap_or_default();
+    let config = CONFIG.load(deps.storage)?;
+    let messages = proposal
+        .kind
+        .to_messages(&env.contract.address, &config.staking_addr)?;
+
+    // Deposits and the keeper pool are only held in trust, a proposal
+    // reaching into them fails instead of breaking later refunds
+    let spent = gov_tokens_spent(&messages, &config.gov_token_denom);
+    if !spent.is_zero() && spent > spendable_gov_tokens(deps.as_ref(), &env, &config)? {
+        proposal.status = ProposalStatus::Failed;
+        proposals().save(deps.storage, proposal_id, &proposal)?;
+        let error = ContractError::EscrowedFundsSpent {}.to_string();
+        EXECUTION_ERRORS.save(deps.storage, proposal_id, &error)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "execute_proposal"),
+            attr("proposal_id", proposal_id.to_string()),
+            attr("status", proposal.status.to_string()),
+        ]));
+    }
+
+    // The outcome of every message is reported back in `reply`,
+    // a proposal without messages is executed right away
+    proposal.status = if messages.is_empty() {
+        ProposalStatus::Executed
+    } else {
+        ProposalStatus::InProgress
+    };
+    proposals().save(deps.storage, proposal_id, &proposal)?;
 
     Ok(Response::new()
         .add_attribute("action", "execute_proposal")
         .add_attribute("proposal_id", proposal_id.to_string())
-        .add_messages(messages))
+        .add_submessages(
+            messages
+                .into_iter()
+                .map(|msg| SubMsg::reply_always(msg, proposal_id)),
+        ))
+}
+
+/// Sums up the governance tokens the messages send away from the contract.
+fn gov_tokens_spent(messages: &[CosmosMsg], denom: &str) -> Uint128 {
+    messages
+        .iter()
+        .flat_map(|msg| match msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. })
+            | CosmosMsg::Bank(BankMsg::Burn { amount })
+            | CosmosMsg::Wasm(WasmMsg::Execute { funds: amount, .. })
+            | CosmosMsg::Wasm(WasmMsg::Instantiate { funds: amount, .. }) => amount.as_slice(),
+            _ => &[],
+        })
+        .filter(|coin| coin.denom == denom)
+        .map(|coin| coin.amount)
+        .sum()
+}
+
+/// Returns the governance tokens of the contract that are not held
+/// for open proposal deposits or the keeper pool.
+fn spendable_gov_tokens(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    let mut escrowed = KEEPER_POOL.may_load(deps.storage)?.unwrap_or_default();
+    for status in [ProposalStatus::Deposit, ProposalStatus::Active] {
+        for item in proposals()
+            .idx
+            .status
+            .prefix(status.to_string())
+            .range(deps.storage, None, None, Order::Ascending)
+        {
+            escrowed += item?.1.deposit_amount;
+        }
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.gov_token_denom)?
+        .amount;
+    Ok(balance.saturating_sub(escrowed))
+}
+
+/// Records the outcome of a proposal message. The proposal is executed once
+/// its messages succeed and fails as soon as one of them returns an error.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let proposal_id = msg.id;
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
+
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            if proposal.status != ProposalStatus::Failed {
+                proposal.status = ProposalStatus::Executed;
+            }
+        }
+        SubMsgResult::Err(err) => {
+            if proposal.status != ProposalStatus::Failed {
+                proposal.status = ProposalStatus::Failed;
+                EXECUTION_ERRORS.save(deps.storage, proposal_id, &err)?;
+            }
+        }
+    }
+    proposals().save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_proposal_reply")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("status", proposal.status.to_string()))
+}
+
+/// Lets the guardian stop a passed proposal before its effective delay ends.
+pub fn veto_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
+
+    if proposal.status != ProposalStatus::Passed {
+        return Err(ContractError::ProposalNotPassed {});
+    }
+
+    if proposal.delay_end.is_expired(&env.block) {
+        return Err(ContractError::ProposalDelayEnded {});
+    }
+
+    proposal.status = ProposalStatus::Vetoed;
+    proposals().save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "veto_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("guardian", info.sender),
+    ]))
 }
 
 /// Checks that proposal messages are correct.
@@ -296,46 +716,250 @@ pub fn check_messages(env: Env, mut messages: Vec<CosmosMsg>) -> Result<Response
         .add_messages(messages))
 }
 
-/// Removes an expired or rejected proposal from the general proposal list.
-pub fn remove_completed_proposal(
+/// Cancels a proposal that has no votes yet and refunds its depositors.
+pub fn cancel_proposal(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     proposal_id: u64,
 ) -> Result<Response, ContractError> {
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
+
+    if proposal.submitter != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !matches!(proposal.status, ProposalStatus::Deposit | ProposalStatus::Active) {
+        return Err(ContractError::ProposalNotActive {});
+    }
+
+    // Once voting is over the deposit is handled by ending the proposal
+    if proposal.status == ProposalStatus::Active && proposal.voting_end.is_expired(&env.block) {
+        return Err(ContractError::VotingPeriodEnded {});
+    }
+
+    let has_votes = VOTES
+        .prefix(proposal_id)
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if has_votes {
+        return Err(ContractError::ProposalHasVotes {});
+    }
+
+    proposal.status = ProposalStatus::Cancelled;
+    proposal.deposit_destination = Some(DepositDestination::Refund);
+    proposals().save(deps.storage, proposal_id, &proposal)?;
+
     let config = CONFIG.load(deps.storage)?;
 
-    let mut proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    Ok(Response::new()
+        .add_attribute("action", "cancel_proposal")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_messages(refund_deposits(deps.as_ref(), proposal_id, &config.gov_token_denom)?))
+}
+
+/// Removes an expired, rejected, cancelled or vetoed proposal from the general proposal list.
+pub fn remove_completed_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = proposals().load(deps.storage, proposal_id)?;
 
-    if env.block.height
-        > (proposal.end_block + config.proposal_effective_delay + config.proposal_expiration_period)
+    // Proposals still holding deposits have to be ended first to settle them
+    if !matches!(proposal.status, ProposalStatus::Deposit | ProposalStatus::Active)
+        && proposal.expiration.is_expired(&env.block)
     {
         proposal.status = ProposalStatus::Expired;
     }
 
-    if proposal.status != ProposalStatus::Expired && proposal.status != ProposalStatus::Rejected {
+    if !matches!(
+        proposal.status,
+        ProposalStatus::Expired
+            | ProposalStatus::Rejected
+            | ProposalStatus::Cancelled
+            | ProposalStatus::Vetoed
+    ) {
         return Err(ContractError::ProposalNotCompleted {});
     }
 
-    PROPOSALS.remove(deps.storage, proposal_id);
+    proposals().remove(deps.storage, proposal_id)?;
+    remove_proposal_records(deps.storage, proposal_id)?;
 
     Ok(Response::new()
         .add_attribute("action", "remove_completed_proposal")
         .add_attribute("proposal_id", proposal_id.to_string()))
 }
 
+/// Removes the votes, deposits and execution error kept for a proposal.
+fn remove_proposal_records(storage: &mut dyn Storage, proposal_id: u64) -> StdResult<()> {
+    let voters = VOTES
+        .prefix(proposal_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for voter in voters {
+        VOTES.remove(storage, (proposal_id, &voter));
+    }
+
+    let option_voters = OPTION_VOTERS
+        .sub_prefix(proposal_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (option, voter) in option_voters {
+        OPTION_VOTERS.remove(storage, (proposal_id, &option, &voter));
+    }
+
+    let depositors = DEPOSITS
+        .prefix(proposal_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for depositor in depositors {
+        DEPOSITS.remove(storage, (proposal_id, &depositor));
+    }
+
+    EXECUTION_ERRORS.remove(storage, proposal_id);
+    Ok(())
+}
+
+/// Ends, executes or expires the first `limit` proposals that are due in the order they were submitted.
+/// Each status is only scanned up to its first proposal that is not due yet,
+/// later ones are picked up by a following crank once it is.
+/// The caller earns the keeper reward, as far as the pool covers it, for every passed proposal
+/// executed or expired and every proposal ended without refunding its deposit.
+/// Refunded proposals cost their submitter nothing and would otherwise drain the pool.
+pub fn crank(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // Only proposals waiting for a deadline can be due, merged back into submission order
+    let mut due_proposals = vec![];
+    for status in [ProposalStatus::Deposit, ProposalStatus::Active, ProposalStatus::Passed] {
+        let due = proposals()
+            .idx
+            .status
+            .prefix(status.to_string())
+            .range(deps.storage, None, None, Order::Ascending)
+            .take_while(|item| item.as_ref().map_or(true, |(_, proposal)| is_due(proposal, &env)))
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        due_proposals.extend(due);
+    }
+    due_proposals.sort_by_key(|(proposal_id, _)| *proposal_id);
+    due_proposals.truncate(limit);
+
+    let mut rewarded = 0u128;
+    let mut response = Response::new().add_attribute("action", "crank");
+    for (proposal_id, mut proposal) in due_proposals.iter().cloned() {
+        let passed = proposal.status == ProposalStatus::Passed;
+        let cranked = match proposal.status {
+            ProposalStatus::Passed if proposal.expiration.is_expired(&env.block) => {
+                proposal.status = ProposalStatus::Expired;
+                proposals().save(deps.storage, proposal_id, &proposal)?;
+
+                Response::new().add_attributes(vec![
+                    attr("action", "expire_proposal"),
+                    attr("proposal_id", proposal_id.to_string()),
+                ])
+            }
+            ProposalStatus::Passed => execute_proposal(deps.branch(), env.clone(), proposal_id)?,
+            _ => end_proposal(deps.branch(), env.clone(), proposal_id)?,
+        };
+
+        let refunded = matches!(
+            proposals().load(deps.storage, proposal_id)?.deposit_destination,
+            None | Some(DepositDestination::Refund)
+        );
+        if passed || !refunded {
+            rewarded += 1;
+        }
+
+        response = response
+            .add_submessages(cranked.messages)
+            .add_events(cranked.events)
+            .add_event(Event::new("crank_proposal").add_attributes(cranked.attributes));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let pool = KEEPER_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let reward = config
+        .keeper_reward
+        .saturating_mul(Uint128::from(rewarded))
+        .min(pool);
+
+    if !reward.is_zero() {
+        KEEPER_POOL.save(deps.storage, &(pool - reward))?;
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: config.gov_token_denom,
+                amount: reward,
+            }],
+        });
+    }
+
+    Ok(response.add_attributes(vec![
+        attr("cranked", due_proposals.len().to_string()),
+        attr("keeper_reward", reward),
+    ]))
+}
+
+/// Checks whether a proposal waits for somebody to end, execute or expire it.
+fn is_due(proposal: &Proposal, env: &Env) -> bool {
+    match proposal.status {
+        ProposalStatus::Deposit => proposal.deposit_end.is_expired(&env.block),
+        ProposalStatus::Active => proposal.voting_end.is_expired(&env.block),
+        ProposalStatus::Passed => proposal.delay_end.is_expired(&env.block),
+        _ => false,
+    }
+}
+
+/// Adds the attached governance tokens to the keeper pool.
+pub fn fund_keeper_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let funds = validate_funds(deps.storage, info.funds)?;
+
+    let pool = KEEPER_POOL
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(funds.amount)?;
+    KEEPER_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "fund_keeper_pool"),
+        attr("amount", funds.amount),
+        attr("keeper_pool", pool),
+    ]))
+}
+
 pub fn update_config(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    updated_config: Box<UpdateConfig>,
+    updated_config: UpdateConfig,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
 
     // Only the Assembly is allowed to update its own parameters (through a successful proposal)
     if info.sender != env.contract.address {
         return Err(ContractError::Unauthorized {});
     }
 
+    let config = apply_config_update(deps.api, config, updated_config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// Applies an [`UpdateConfig`] to `config` and validates the result.
+fn apply_config_update(
+    api: &dyn Api,
+    mut config: Config,
+    updated_config: UpdateConfig,
+) -> Result<Config, ContractError> {
     if let Some(proposal_voting_period) = updated_config.proposal_voting_period {
         config.proposal_voting_period = proposal_voting_period;
     }
@@ -352,6 +976,22 @@ pub fn update_config(
         config.proposal_required_deposit = Uint128::from(proposal_required_deposit);
     }
 
+    if let Some(proposal_deposit_period) = updated_config.proposal_deposit_period {
+        config.proposal_deposit_period = proposal_deposit_period;
+    }
+
+    if let Some(proposal_min_initial_deposit) = updated_config.proposal_min_initial_deposit {
+        config.proposal_min_initial_deposit = Decimal::from_str(&proposal_min_initial_deposit)?;
+    }
+
+    if let Some(destination) = updated_config.no_quorum_deposit_destination {
+        config.no_quorum_deposit_destination = validate_deposit_destination(api, destination)?;
+    }
+
+    if let Some(destination) = updated_config.veto_deposit_destination {
+        config.veto_deposit_destination = validate_deposit_destination(api, destination)?;
+    }
+
     if let Some(proposal_required_quorum) = updated_config.proposal_required_quorum {
         config.proposal_required_quorum = Decimal::from_str(&proposal_required_quorum)?;
     }
@@ -360,47 +1000,139 @@ pub fn update_config(
         config.proposal_required_threshold = Decimal::from_str(&proposal_required_threshold)?;
     }
 
+    if let Some(proposal_veto_threshold) = updated_config.proposal_veto_threshold {
+        config.proposal_veto_threshold = Decimal::from_str(&proposal_veto_threshold)?;
+    }
+
+    if let Some(guardian) = updated_config.guardian {
+        config.guardian = Some(api.addr_validate(&guardian)?);
+    }
+
+    if updated_config.remove_guardian == Some(true) {
+        config.guardian = None;
+    }
+
+    if let Some(keeper_reward) = updated_config.keeper_reward {
+        config.keeper_reward = Uint128::from(keeper_reward);
+    }
+
+    if let Some(expedited_voting_period) = updated_config.expedited_voting_period {
+        config.expedited_voting_period = expedited_voting_period;
+    }
+
+    if let Some(expedited_required_deposit) = updated_config.expedited_required_deposit {
+        config.expedited_required_deposit = Uint128::from(expedited_required_deposit);
+    }
+
+    if let Some(expedited_required_quorum) = updated_config.expedited_required_quorum {
+        config.expedited_required_quorum = Decimal::from_str(&expedited_required_quorum)?;
+    }
+
+    if let Some(expedited_required_threshold) = updated_config.expedited_required_threshold {
+        config.expedited_required_threshold = Decimal::from_str(&expedited_required_threshold)?;
+    }
+
     config.validate()?;
 
-    CONFIG.save(deps.storage, &config)?;
+    Ok(config)
+}
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+/// Migrates the contract state to the current version.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    match get_contract_version(deps.storage) {
+        Ok(version) if version.contract != CONTRACT_NAME => {
+            return Err(ContractError::MigrationError {});
+        }
+        Ok(_) => {}
+        // Only the first deployed version carries no version info
+        Err(_) => {
+            // The proposals are migrated with the voting power queries of the current config
+            migrate_config(deps.branch())?;
+            migrate_proposals(deps.branch())?;
+        }
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attribute("action", "migrate"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
-        QueryMsg::Proposals { start, limit } => to_binary(&query_proposals(deps, start, limit)?),
+        QueryMsg::Proposals { start, limit, order } => {
+            to_binary(&query_proposals(deps, start, limit, order)?)
+        }
+        QueryMsg::ProposalsByStatus {
+            status,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_proposals_by_status(
+            deps,
+            status,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::ProposalsBySubmitter {
+            submitter,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_proposals_by_submitter(
+            deps,
+            submitter,
+            start_after,
+            limit,
+            order,
+        )?),
         QueryMsg::Proposal { proposal_id } => {
-            to_binary(&PROPOSALS.load(deps.storage, proposal_id)?)
+            to_binary(&proposals().load(deps.storage, proposal_id)?)
         }
         QueryMsg::ProposalVotes { proposal_id } => {
             to_binary(&query_proposal_votes(deps, proposal_id)?)
         }
         QueryMsg::UserVotingPower { user, proposal_id } => {
-            let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+            let proposal = proposals().load(deps.storage, proposal_id)?;
 
             deps.api.addr_validate(&user)?;
 
             to_binary(&calc_voting_power(deps, user, &proposal)?)
         }
         QueryMsg::TotalVotingPower { proposal_id } => {
-            let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
-            to_binary(&calc_total_voting_power_at(deps, &proposal)?)
+            let proposal = proposals().load(deps.storage, proposal_id)?;
+            to_binary(&proposal.total_voting_power)
+        }
+        QueryMsg::ExecutionResult { proposal_id } => {
+            let proposal = proposals().load(deps.storage, proposal_id)?;
+            to_binary(&ProposalExecutionResponse {
+                proposal_id,
+                status: proposal.status,
+                error: EXECUTION_ERRORS.may_load(deps.storage, proposal_id)?,
+            })
+        }
+        QueryMsg::KeeperPool {} => {
+            to_binary(&KEEPER_POOL.may_load(deps.storage)?.unwrap_or_default())
         }
         QueryMsg::ProposalVoters {
             proposal_id,
             vote_option,
-            start,
+            start_after,
             limit,
         } => to_binary(&query_proposal_voters(
             deps,
             proposal_id,
             vote_option,
-            start,
+            start_after,
             limit,
         )?),
+        QueryMsg::Vote { proposal_id, voter } => {
+            let voter = deps.api.addr_validate(&voter)?;
+            to_binary(&VOTES.may_load(deps.storage, (proposal_id, &voter))?)
+        }
     }
 }
 
@@ -409,14 +1141,74 @@ pub fn query_proposals(
     deps: Deps,
     start: Option<u64>,
     limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<ProposalListResponse> {
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = order_bounds(start.map(Bound::inclusive), order);
+
+    let items = proposals().range(deps.storage, min, max, order);
+    proposal_list(deps, items, limit)
+}
+
+/// Returns the proposals with the given status.
+pub fn query_proposals_by_status(
+    deps: Deps,
+    status: ProposalStatus,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<ProposalListResponse> {
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = order_bounds(start_after.map(Bound::exclusive), order);
+
+    let items = proposals()
+        .idx
+        .status
+        .prefix(status.to_string())
+        .range(deps.storage, min, max, order);
+    proposal_list(deps, items, limit)
+}
+
+/// Returns the proposals submitted by the given address.
+pub fn query_proposals_by_submitter(
+    deps: Deps,
+    submitter: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
 ) -> StdResult<ProposalListResponse> {
-    let proposal_count = PROPOSAL_COUNT.load(deps.storage)?;
+    let submitter = deps.api.addr_validate(&submitter)?;
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = order_bounds(start_after.map(Bound::exclusive), order);
+
+    let items = proposals()
+        .idx
+        .submitter
+        .prefix(submitter)
+        .range(deps.storage, min, max, order);
+    proposal_list(deps, items, limit)
+}
 
+/// Puts the start of a page on the side the proposals are walked from.
+fn order_bounds(
+    start: Option<Bound<u64>>,
+    order: Order,
+) -> (Option<Bound<u64>>, Option<Bound<u64>>) {
+    match order {
+        Order::Ascending => (start, None),
+        Order::Descending => (None, start),
+    }
+}
+
+/// Collects a page of proposals into a [`ProposalListResponse`].
+fn proposal_list(
+    deps: Deps,
+    items: impl Iterator<Item = StdResult<(u64, Proposal)>>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start.map(Bound::inclusive);
 
-    let proposal_list = PROPOSALS
-        .range(deps.storage, start, None, Order::Ascending)
+    let proposal_list = items
         .take(limit)
         .map(|item| {
             let (_, v) = item?;
@@ -425,7 +1217,7 @@ pub fn query_proposals(
         .collect::<StdResult<Vec<_>>>()?;
 
     Ok(ProposalListResponse {
-        proposal_count,
+        proposal_count: PROPOSAL_COUNT.load(deps.storage)?,
         proposal_list,
     })
 }
@@ -435,62 +1227,57 @@ pub fn query_proposal_voters(
     deps: Deps,
     proposal_id: u64,
     vote_option: ProposalVoteOption,
-    start: Option<u64>,
+    start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<Vec<Addr>> {
-    let limit = limit.unwrap_or(DEFAULT_VOTERS_LIMIT).min(MAX_VOTERS_LIMIT);
-    let start = start.unwrap_or_default();
-
-    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
-
-    let voters = match vote_option {
-        ProposalVoteOption::For => proposal.yes_voters,
-        ProposalVoteOption::Against => proposal.no_voters,
-    };
-
-    Ok(voters
-        .iter()
-        .skip(start as usize)
-        .take(limit as usize)
-        .cloned()
-        .collect())
+    let limit = limit.unwrap_or(DEFAULT_VOTERS_LIMIT).min(MAX_VOTERS_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    OPTION_VOTERS
+        .prefix((proposal_id, &vote_option.to_string()))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
 }
 
 /// Returns proposal votes stored in the [`ProposalVotesResponse`] structure.
 pub fn query_proposal_votes(deps: Deps, proposal_id: u64) -> StdResult<ProposalVotesResponse> {
-    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    let proposal = proposals().load(deps.storage, proposal_id)?;
 
     Ok(ProposalVotesResponse {
         proposal_id,
         for_power: proposal.yes_power,
         against_power: proposal.no_power,
+        abstain_power: proposal.abstain_power,
+        veto_power: proposal.veto_power,
     })
 }
 
 pub fn calc_voting_power(deps: Deps, sender: String, proposal: &Proposal) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
 
-    // The staked balance of the user is his voting power
-    let staked_user_balance: Uint128 = deps.querier.query_wasm_smart(
+    // The staked balance of the user plus the stakes delegated to them
+    let voting_power: Uint128 = deps.querier.query_wasm_smart(
         config.staking_addr,
-        &StakingQueryMsg::StakedAt {
+        &StakingQueryMsg::VotingPowerAt {
             address: deps.api.addr_validate(&sender.clone())?,
-            height: proposal.start_block - 1,
+            height: proposal.snapshot_height,
         },
     )?;
-    Ok(staked_user_balance)
+    Ok(voting_power)
 
 }
 
-pub fn calc_total_voting_power_at(deps: Deps, proposal: &Proposal) -> StdResult<Uint128> {
+pub fn calc_total_voting_power_at(deps: Deps, height: u64) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
     
     // The staked total balance
     let staked_total_balance: Uint128 = deps.querier.query_wasm_smart(
         config.staking_addr,
-        &StakingQueryMsg::TotalStakedAt {
-            height: proposal.start_block - 1,
-        },
+        &StakingQueryMsg::TotalStakedAt { height },
     )?;
     Ok(staked_total_balance)
 
@@ -0,0 +1,179 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, DepsMut, Empty, Order, StdResult, Uint128, Uint64};
+use cw_storage_plus::{Item, Map};
+use cw_utils::{Duration, Expiration};
+use goblin_governance::{
+    config::{Config, DepositDestination},
+    constants::proposal_constants::*,
+    proposal::{Proposal, ProposalKind, ProposalStatus, ProposalVote, ProposalVoteOption},
+    state::{CONFIG, DEPOSITS, OPTION_VOTERS, VOTES, proposals},
+};
+use std::str::FromStr;
+
+use crate::contract::{calc_total_voting_power_at, calc_voting_power};
+
+/// Config layout of the first deployed version, which counted the proposal
+/// periods in blocks.
+#[cw_serde]
+struct LegacyConfig {
+    pub staking_addr: Addr,
+    pub vesting_addr: Addr,
+    pub gov_token_denom: String,
+    pub proposal_voting_period: u64,
+    pub proposal_effective_delay: u64,
+    pub proposal_expiration_period: u64,
+    pub proposal_required_deposit: Uint128,
+    pub proposal_required_quorum: Decimal,
+    pub proposal_required_threshold: Decimal,
+}
+
+const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// Brings the config of the first deployed version into the current layout.
+/// Block counts become height durations and the settings added since start
+/// out the way that version behaved, the expedited settings as lenient as
+/// the limits allow.
+pub fn migrate_config(deps: DepsMut) -> StdResult<()> {
+    let legacy = LEGACY_CONFIG.load(deps.storage)?;
+
+    let expedited_quorum = Decimal::from_str(MINIMUM_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE)?;
+
+    let config = Config {
+        staking_addr: legacy.staking_addr,
+        vesting_addr: legacy.vesting_addr,
+        gov_token_denom: legacy.gov_token_denom,
+        proposal_voting_period: Duration::Height(legacy.proposal_voting_period),
+        proposal_effective_delay: Duration::Height(legacy.proposal_effective_delay),
+        proposal_expiration_period: Duration::Height(legacy.proposal_expiration_period),
+        proposal_required_deposit: legacy.proposal_required_deposit,
+        proposal_deposit_period: Duration::Height(legacy.proposal_voting_period),
+        proposal_min_initial_deposit: Decimal::percent(MINIMUM_INITIAL_DEPOSIT_PERCENTAGE),
+        proposal_required_quorum: legacy.proposal_required_quorum,
+        proposal_required_threshold: legacy.proposal_required_threshold,
+        proposal_veto_threshold: Decimal::permille(334),
+        expedited_voting_period: Duration::Height(*EXPEDITED_VOTING_PERIOD_INTERVAL.start()),
+        expedited_required_deposit: Uint128::new(*EXPEDITED_DEPOSIT_INTERVAL.start())
+            .max(legacy.proposal_required_deposit),
+        expedited_required_quorum: expedited_quorum.max(legacy.proposal_required_quorum),
+        expedited_required_threshold: Decimal::percent(
+            MINIMUM_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE,
+        )
+        .max(legacy.proposal_required_threshold),
+        // Every deposit used to be refunded
+        no_quorum_deposit_destination: DepositDestination::Refund,
+        veto_deposit_destination: DepositDestination::Refund,
+        guardian: None,
+        keeper_reward: Uint128::zero(),
+    };
+
+    config.validate()?;
+    CONFIG.save(deps.storage, &config)
+}
+
+/// Proposal layout of the first deployed version, which kept the voters
+/// inside the proposal and stored block deadlines.
+#[cw_serde]
+struct LegacyProposal {
+    pub proposal_id: Uint64,
+    pub submitter: Addr,
+    pub status: ProposalStatus,
+    pub yes_power: Uint128,
+    pub no_power: Uint128,
+    pub yes_voters: Vec<Addr>,
+    pub no_voters: Vec<Addr>,
+    pub start_block: u64,
+    pub start_time: u64,
+    pub end_block: u64,
+    pub delayed_end_block: u64,
+    pub expiration_block: u64,
+    pub title: String,
+    pub description: String,
+    pub link: Option<String>,
+    pub messages: Option<Vec<CosmosMsg>>,
+    pub deposit_amount: Uint128,
+}
+
+const LEGACY_PROPOSALS: Map<u64, LegacyProposal> = Map::new("proposals");
+
+/// Brings the proposals of the first deployed version into the current layout.
+/// The voters stored in every proposal move into [`VOTES`] and are listed under
+/// their options, raw messages become a custom proposal kind and block
+/// deadlines become expirations. Active proposals record their deposit for
+/// the submitter, ended ones already refunded it.
+///
+/// The voting power of every voter is queried from the staking contract, one
+/// query per vote, within this single call. The first version was only used
+/// for a handful of proposals, so the gas this takes is accepted rather than
+/// spreading the migration over several transactions.
+pub fn migrate_proposals(deps: DepsMut) -> StdResult<()> {
+    let legacy_proposals = LEGACY_PROPOSALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (proposal_id, legacy) in legacy_proposals {
+        // The snapshot used to be taken the block before the proposal started
+        let snapshot_height = legacy.start_block.saturating_sub(1);
+        let total_voting_power = calc_total_voting_power_at(deps.as_ref(), snapshot_height)?;
+
+        // Proposals were fully funded by their submitter on submission
+        let deposit_destination = if legacy.status == ProposalStatus::Active {
+            DEPOSITS.save(deps.storage, (proposal_id, &legacy.submitter), &legacy.deposit_amount)?;
+            None
+        } else {
+            Some(DepositDestination::Refund)
+        };
+
+        let kind = match legacy.messages {
+            Some(messages) if !messages.is_empty() => ProposalKind::Custom { messages },
+            _ => ProposalKind::Text,
+        };
+
+        // The voting and expiration blocks were still inside their period,
+        // the delay block was not
+        let proposal = Proposal {
+            proposal_id: legacy.proposal_id,
+            submitter: legacy.submitter,
+            status: legacy.status,
+            expedited: false,
+            yes_power: legacy.yes_power,
+            no_power: legacy.no_power,
+            abstain_power: Uint128::zero(),
+            veto_power: Uint128::zero(),
+            deposit_end: Expiration::AtHeight(legacy.start_block),
+            start_block: legacy.start_block,
+            start_time: legacy.start_time,
+            snapshot_height,
+            total_voting_power,
+            voting_end: Expiration::AtHeight(legacy.end_block + 1),
+            delay_end: Expiration::AtHeight(legacy.delayed_end_block),
+            expiration: Expiration::AtHeight(legacy.expiration_block + 1),
+            title: legacy.title,
+            description: legacy.description,
+            link: legacy.link,
+            kind,
+            deposit_amount: legacy.deposit_amount,
+            deposit_destination,
+        };
+
+        let voters = [
+            (ProposalVoteOption::For, legacy.yes_voters),
+            (ProposalVoteOption::Against, legacy.no_voters),
+        ];
+        for (option, voters) in voters {
+            for voter in voters {
+                // The tallies are kept as they are, the voting power of every
+                // voter is taken from the same snapshot the vote was cast with
+                let power = calc_voting_power(deps.as_ref(), voter.to_string(), &proposal)?;
+                let vote = ProposalVote::single(option.clone(), power);
+                VOTES.save(deps.storage, (proposal_id, &voter), &vote)?;
+                OPTION_VOTERS.save(deps.storage, (proposal_id, &option.to_string(), &voter), &Empty {})?;
+            }
+        }
+
+        // The stored value cannot be read in the current layout to clear its
+        // index entries, it has none yet
+        proposals().replace(deps.storage, proposal_id, Some(&proposal), None)?;
+    }
+
+    Ok(())
+}
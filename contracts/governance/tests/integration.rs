@@ -0,0 +1,2109 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, MessageInfo,
+    Response, StdError, StdResult, Timestamp, Uint128, Uint64, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::{Duration, Expiration};
+use goblin_governance::config::{Config, DepositDestination, UpdateConfig};
+use goblin_governance::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg};
+use goblin_governance::proposal::{Proposal, ProposalKind, ProposalStatus, ProposalVote, ProposalVoteOption};
+use goblin_governance::query::{
+    OrderBy, ProposalExecutionResponse, ProposalListResponse, ProposalVotesResponse, QueryMsg,
+};
+use goblin_staking::config::{
+    Config as StakingConfig, PenaltyDestination, StakingToken, UpdateConfig as StakingUpdateConfig,
+};
+use goblin_staking::msg::{ExecuteMsg as StakingExecuteMsg, InstantiateMsg as StakingInstantiateMsg};
+use goblin_staking::query::QueryMsg as StakingQueryMsg;
+use std::str::FromStr;
+
+const DENOM: &str = "ugoblin";
+const VOTING_PERIOD: u64 = 12342;
+const EFFECTIVE_DELAY: u64 = 6171;
+const EXPIRATION_PERIOD: u64 = 12342;
+const REQUIRED_DEPOSIT: u128 = 10_000_000_000;
+const DEPOSIT_PERIOD: u64 = 12342;
+const EXPEDITED_VOTING_PERIOD: u64 = 3000;
+const EXPEDITED_DEPOSIT: u128 = 20_000_000_000;
+const KEEPER_REWARD: u128 = 1_000;
+
+const SUBMITTER: &str = "submitter";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+const CAROL: &str = "carol";
+const DEPOSITOR: &str = "depositor";
+const GUARDIAN: &str = "guardian";
+const KEEPER: &str = "keeper";
+
+fn staking_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        goblin_staking_contract::contract::execute,
+        goblin_staking_contract::contract::instantiate,
+        goblin_staking_contract::contract::query,
+    ))
+}
+
+fn governance_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        goblin_governance_contract::contract::execute,
+        goblin_governance_contract::contract::instantiate,
+        goblin_governance_contract::contract::query,
+    )
+    .with_reply(goblin_governance_contract::contract::reply)
+    .with_migrate(goblin_governance_contract::contract::migrate))
+}
+
+/// Config layout of the first deployed version.
+#[cw_serde]
+struct BaselineConfig {
+    staking_addr: Addr,
+    vesting_addr: Addr,
+    gov_token_denom: String,
+    proposal_voting_period: u64,
+    proposal_effective_delay: u64,
+    proposal_expiration_period: u64,
+    proposal_required_deposit: Uint128,
+    proposal_required_quorum: Decimal,
+    proposal_required_threshold: Decimal,
+}
+
+/// Proposal layout of the first deployed version.
+#[cw_serde]
+struct BaselineProposal {
+    proposal_id: Uint64,
+    submitter: Addr,
+    status: ProposalStatus,
+    yes_power: Uint128,
+    no_power: Uint128,
+    yes_voters: Vec<Addr>,
+    no_voters: Vec<Addr>,
+    start_block: u64,
+    start_time: u64,
+    end_block: u64,
+    delayed_end_block: u64,
+    expiration_block: u64,
+    title: String,
+    description: String,
+    link: Option<String>,
+    messages: Option<Vec<CosmosMsg>>,
+    deposit_amount: Uint128,
+}
+
+#[cw_serde]
+struct BaselineState {
+    config: BaselineConfig,
+    proposals: Vec<BaselineProposal>,
+}
+
+fn store_baseline_state(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    state: BaselineState,
+) -> StdResult<Response> {
+    Item::new("config").save(deps.storage, &state.config)?;
+    Item::new("proposal_count").save(deps.storage, &Uint64::new(state.proposals.len() as u64))?;
+    for proposal in state.proposals {
+        Map::new("proposals").save(deps.storage, proposal.proposal_id.u64(), &proposal)?;
+    }
+    Ok(Response::new())
+}
+
+fn no_execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err("baseline state only"))
+}
+
+fn no_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Err(StdError::generic_err("baseline state only"))
+}
+
+/// Stands in for a deployment of the first version by writing its state as is.
+fn baseline_governance_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(no_execute, store_baseline_state, no_query))
+}
+
+struct Suite {
+    app: App,
+    staking: Addr,
+    governance: Addr,
+}
+
+impl Suite {
+    fn new() -> Self {
+        Self::with_deposit_destinations(DepositDestination::Refund, DepositDestination::Burn)
+    }
+
+    fn with_deposit_destinations(
+        no_quorum_deposit_destination: DepositDestination,
+        veto_deposit_destination: DepositDestination,
+    ) -> Self {
+        let mut app = App::new(|router, _, storage| {
+            for (user, amount) in [
+                (SUBMITTER, 2 * REQUIRED_DEPOSIT),
+                (ALICE, 1_000_000),
+                (BOB, 1_000_000),
+                (CAROL, 1_000_000),
+                (DEPOSITOR, REQUIRED_DEPOSIT),
+            ] {
+                router
+                    .bank
+                    .init_balance(storage, &Addr::unchecked(user), coins(amount, DENOM))
+                    .unwrap();
+            }
+        });
+
+        let staking_code_id = app.store_code(staking_contract());
+        let governance_code_id = app.store_code(governance_contract());
+
+        let staking = app
+            .instantiate_contract(
+                staking_code_id,
+                Addr::unchecked("owner"),
+                &StakingInstantiateMsg {
+                    config: StakingConfig {
+                        owner: Addr::unchecked("owner"),
+                        token: StakingToken::Native {
+                            denom: DENOM.to_string(),
+                        },
+                        lock_period: 86400,
+                        claim_epoch: 3600,
+                        max_claims: 10,
+                        early_exit_penalty: Decimal::zero(),
+                        penalty_destination: PenaltyDestination::Burn,
+                        reward_denoms: vec![],
+                    },
+                },
+                &[],
+                "goblin-staking",
+                None,
+            )
+            .unwrap();
+
+        let governance = app
+            .instantiate_contract(
+                governance_code_id,
+                Addr::unchecked("owner"),
+                &InstantiateMsg {
+                    staking_addr: staking.to_string(),
+                    vesting_addr: "vesting".to_string(),
+                    gov_token_denom: DENOM.to_string(),
+                    proposal_voting_period: Duration::Height(VOTING_PERIOD),
+                    proposal_effective_delay: Duration::Height(EFFECTIVE_DELAY),
+                    proposal_expiration_period: Duration::Height(EXPIRATION_PERIOD),
+                    proposal_required_deposit: Uint128::new(REQUIRED_DEPOSIT),
+                    proposal_deposit_period: Duration::Height(DEPOSIT_PERIOD),
+                    proposal_min_initial_deposit: "0.25".to_string(),
+                    proposal_required_quorum: "0.1".to_string(),
+                    proposal_required_threshold: "0.5".to_string(),
+                    proposal_veto_threshold: "0.334".to_string(),
+                    expedited_voting_period: Duration::Height(EXPEDITED_VOTING_PERIOD),
+                    expedited_required_deposit: Uint128::new(EXPEDITED_DEPOSIT),
+                    expedited_required_quorum: "0.2".to_string(),
+                    expedited_required_threshold: "0.667".to_string(),
+                    no_quorum_deposit_destination,
+                    veto_deposit_destination,
+                    guardian: Some(GUARDIAN.to_string()),
+                    keeper_reward: Uint128::new(KEEPER_REWARD),
+                },
+                &[],
+                "goblin-governance",
+                Some("owner".to_string()),
+            )
+            .unwrap();
+
+        Suite {
+            app,
+            staking,
+            governance,
+        }
+    }
+
+    fn advance_blocks(&mut self, blocks: u64) {
+        self.app.update_block(|b| {
+            b.height += blocks;
+            b.time = b.time.plus_seconds(7 * blocks);
+        });
+    }
+
+    fn advance_time(&mut self, seconds: u64) {
+        self.app.update_block(|b| {
+            b.height += 1;
+            b.time = b.time.plus_seconds(seconds);
+        });
+    }
+
+    fn stake(&mut self, staker: &str, amount: u128) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(staker),
+                self.staking.clone(),
+                &StakingExecuteMsg::Stake {},
+                &coins(amount, DENOM),
+            )
+            .unwrap();
+    }
+
+    fn submit_proposal(&mut self, kind: ProposalKind) -> anyhow::Result<u64> {
+        self.submit_proposal_with_deposit(kind, REQUIRED_DEPOSIT)
+    }
+
+    fn submit_proposal_with_deposit(
+        &mut self,
+        kind: ProposalKind,
+        deposit: u128,
+    ) -> anyhow::Result<u64> {
+        self.submit(kind, deposit, false)
+    }
+
+    fn submit_expedited_proposal(&mut self, deposit: u128) -> anyhow::Result<u64> {
+        self.submit(ProposalKind::Text, deposit, true)
+    }
+
+    fn submit(&mut self, kind: ProposalKind, deposit: u128, expedited: bool) -> anyhow::Result<u64> {
+        self.submit_from(SUBMITTER, kind, deposit, expedited)
+    }
+
+    fn submit_from(
+        &mut self,
+        submitter: &str,
+        kind: ProposalKind,
+        deposit: u128,
+        expedited: bool,
+    ) -> anyhow::Result<u64> {
+        let res = self.app.execute_contract(
+            Addr::unchecked(submitter),
+            self.governance.clone(),
+            &ExecuteMsg::SubmitProposal {
+                title: "Raise the threshold".to_string(),
+                description: "Make proposals harder to pass".to_string(),
+                link: None,
+                kind,
+                expedited,
+            },
+            &coins(deposit, DENOM),
+        )?;
+        let proposal_id = res
+            .events
+            .iter()
+            .flat_map(|e| e.attributes.iter())
+            .find(|a| a.key == "proposal_id")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap();
+        Ok(proposal_id)
+    }
+
+    fn add_deposit(&mut self, depositor: &str, proposal_id: u64, amount: u128) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            Addr::unchecked(depositor),
+            self.governance.clone(),
+            &ExecuteMsg::AddDeposit { proposal_id },
+            &coins(amount, DENOM),
+        )?;
+        Ok(())
+    }
+
+    fn vote(&mut self, voter: &str, proposal_id: u64, vote: ProposalVoteOption) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            Addr::unchecked(voter),
+            self.governance.clone(),
+            &ExecuteMsg::CastVote { proposal_id, vote },
+            &[],
+        )?;
+        Ok(())
+    }
+
+    fn end_proposal(&mut self, proposal_id: u64) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            Addr::unchecked(CAROL),
+            self.governance.clone(),
+            &ExecuteMsg::EndProposal { proposal_id },
+            &[],
+        )?;
+        Ok(())
+    }
+
+    fn execute_proposal(&mut self, proposal_id: u64) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            Addr::unchecked(CAROL),
+            self.governance.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id },
+            &[],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the number of proposals moved forward.
+    fn crank(&mut self, limit: Option<u32>) -> u64 {
+        let res = self
+            .app
+            .execute_contract(
+                Addr::unchecked(KEEPER),
+                self.governance.clone(),
+                &ExecuteMsg::Crank { limit },
+                &[],
+            )
+            .unwrap();
+        res.events
+            .iter()
+            .flat_map(|e| e.attributes.iter())
+            .find(|a| a.key == "cranked")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap()
+    }
+
+    fn fund_keeper_pool(&mut self, funder: &str, amount: u128) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(funder),
+                self.governance.clone(),
+                &ExecuteMsg::FundKeeperPool {},
+                &coins(amount, DENOM),
+            )
+            .unwrap();
+    }
+
+    fn query_keeper_pool(&self) -> Uint128 {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.governance, &QueryMsg::KeeperPool {})
+            .unwrap()
+    }
+
+    fn query_proposal(&self, proposal_id: u64) -> Proposal {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.governance, &QueryMsg::Proposal { proposal_id })
+            .unwrap()
+    }
+
+    /// Returns the ids of the proposals in a proposal list query.
+    fn query_proposal_ids(&self, msg: QueryMsg) -> Vec<u64> {
+        let res: ProposalListResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.governance, &msg)
+            .unwrap();
+        res.proposal_list
+            .iter()
+            .map(|proposal| proposal.proposal_id.u64())
+            .collect()
+    }
+
+    fn query_by_status(
+        &self,
+        status: ProposalStatus,
+        start_after: Option<u64>,
+        order: Option<OrderBy>,
+    ) -> Vec<u64> {
+        self.query_proposal_ids(QueryMsg::ProposalsByStatus {
+            status,
+            start_after,
+            limit: None,
+            order,
+        })
+    }
+
+    fn query_by_submitter(&self, submitter: &str, order: Option<OrderBy>) -> Vec<u64> {
+        self.query_proposal_ids(QueryMsg::ProposalsBySubmitter {
+            submitter: submitter.to_string(),
+            start_after: None,
+            limit: None,
+            order,
+        })
+    }
+
+    fn query_config(&self) -> Config {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.governance, &QueryMsg::Config {})
+            .unwrap()
+    }
+
+    fn query_voters(
+        &self,
+        proposal_id: u64,
+        vote_option: ProposalVoteOption,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> Vec<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.governance,
+                &QueryMsg::ProposalVoters {
+                    proposal_id,
+                    vote_option,
+                    start_after: start_after.map(str::to_string),
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
+    fn query_execution_result(&self, proposal_id: u64) -> ProposalExecutionResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.governance, &QueryMsg::ExecutionResult { proposal_id })
+            .unwrap()
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        self.app.wrap().query_balance(address, DENOM).unwrap().amount
+    }
+
+    fn update_threshold_msg(&self, threshold: &str) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.governance.to_string(),
+            msg: to_binary(&ExecuteMsg::UpdateConfig(Box::new(UpdateConfig {
+                proposal_voting_period: None,
+                proposal_effective_delay: None,
+                proposal_expiration_period: None,
+                proposal_required_deposit: None,
+                proposal_deposit_period: None,
+                proposal_min_initial_deposit: None,
+                proposal_required_quorum: None,
+                proposal_required_threshold: Some(threshold.to_string()),
+                proposal_veto_threshold: None,
+                expedited_voting_period: None,
+                expedited_required_deposit: None,
+                expedited_required_quorum: None,
+                expedited_required_threshold: None,
+                no_quorum_deposit_destination: None,
+                veto_deposit_destination: None,
+                guardian: None,
+                remove_guardian: None,
+                keeper_reward: None,
+            })))
+            .unwrap(),
+            funds: vec![],
+        })
+    }
+}
+
+#[test]
+fn stake_propose_vote_end_execute() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.stake(BOB, 300_000);
+    suite.stake(CAROL, 100_000);
+    suite.advance_blocks(2);
+
+    let msg = suite.update_threshold_msg("0.6");
+    let proposal_id = suite.submit_proposal(ProposalKind::Custom { messages: vec![msg] }).unwrap();
+    assert_eq!(proposal_id, 1);
+    assert_eq!(suite.balance(SUBMITTER).u128(), REQUIRED_DEPOSIT);
+
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::Against).unwrap();
+
+    let votes: ProposalVotesResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.governance, &QueryMsg::ProposalVotes { proposal_id })
+        .unwrap();
+    assert_eq!(votes.for_power, Uint128::new(600_000));
+    assert_eq!(votes.against_power, Uint128::new(300_000));
+
+    let total_power: Uint128 = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.governance, &QueryMsg::TotalVotingPower { proposal_id })
+        .unwrap();
+    assert_eq!(total_power, Uint128::new(1_000_000));
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Passed);
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+
+    // the effective delay has to pass before the proposal can be executed
+    suite.execute_proposal(proposal_id).unwrap_err();
+
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Executed);
+    assert_eq!(
+        suite.query_config().proposal_required_threshold,
+        Decimal::percent(60)
+    );
+}
+
+#[test]
+fn proposal_without_quorum_is_rejected() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 50_000);
+    suite.stake(BOB, 950_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Rejected);
+
+    let err = suite.execute_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not passed!");
+}
+
+#[test]
+fn voting_rules_are_enforced() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 500_000);
+    suite.advance_blocks(2);
+
+    // stake added after submission does not count for this proposal
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.stake(BOB, 500_000);
+
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    let err = suite
+        .vote(BOB, proposal_id, ProposalVoteOption::For)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "You don't have any voting power!");
+
+    let err = suite.end_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Voting period not ended yet!");
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    let err = suite
+        .vote(CAROL, proposal_id, ProposalVoteOption::For)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Voting period ended!");
+}
+
+#[test]
+fn submit_proposal_requires_deposit() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(SUBMITTER),
+            suite.governance.clone(),
+            &ExecuteMsg::SubmitProposal {
+                title: "Raise the threshold".to_string(),
+                description: "Make proposals harder to pass".to_string(),
+                link: None,
+                kind: ProposalKind::Text,
+                expedited: false,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Generic error: funds shall only contain one denom");
+
+    // Without the full deposit the proposal waits in the deposit period
+    let proposal_id = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT - 1)
+        .unwrap();
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Deposit);
+    assert_eq!(proposal.voting_end, Expiration::Never {});
+
+    let err = suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not active!");
+}
+
+#[test]
+fn governance_takes_over_and_tunes_staking() {
+    let mut suite = Suite::new();
+
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            suite.staking.clone(),
+            &StakingExecuteMsg::ProposeNewOwner {
+                owner: suite.governance.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let staking_msg = |msg: &StakingExecuteMsg| {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: suite.staking.to_string(),
+            msg: to_binary(msg).unwrap(),
+            funds: vec![],
+        })
+    };
+    let messages = vec![
+        staking_msg(&StakingExecuteMsg::AcceptOwnership {}),
+        staking_msg(&StakingExecuteMsg::UpdateConfig(StakingUpdateConfig {
+            lock_period: Some(7 * 86400),
+            max_claims: None,
+            early_exit_penalty: None,
+            penalty_destination: None,
+            reward_denoms: None,
+        })),
+    ];
+    let proposal_id = suite.submit_proposal(ProposalKind::Custom { messages }).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(proposal_id).unwrap();
+
+    let config: StakingConfig = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.staking, &StakingQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.owner, suite.governance);
+    assert_eq!(config.lock_period, 7 * 86400);
+}
+
+#[test]
+fn delegates_vote_with_delegated_power() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 300_000);
+    suite.stake(BOB, 100_000);
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            suite.staking.clone(),
+            &StakingExecuteMsg::DelegateVotes {
+                to: BOB.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+
+    let err = suite
+        .vote(ALICE, proposal_id, ProposalVoteOption::Against)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "You don't have any voting power!");
+
+    suite.vote(BOB, proposal_id, ProposalVoteOption::For).unwrap();
+    let votes: ProposalVotesResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.governance, &QueryMsg::ProposalVotes { proposal_id })
+        .unwrap();
+    assert_eq!(votes.for_power, Uint128::new(400_000));
+}
+
+#[test]
+fn abstain_counts_towards_quorum_only() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 50_000);
+    suite.stake(BOB, 200_000);
+    suite.stake(CAROL, 750_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::Abstain).unwrap();
+
+    let votes: ProposalVotesResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.governance, &QueryMsg::ProposalVotes { proposal_id })
+        .unwrap();
+    assert_eq!(votes.for_power, Uint128::new(50_000));
+    assert_eq!(votes.abstain_power, Uint128::new(200_000));
+
+    // 5% for votes alone would miss the 10% quorum
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Passed);
+}
+
+#[test]
+fn vetoed_proposal_burns_deposit() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 300_000);
+    suite.stake(BOB, 400_000);
+    suite.stake(CAROL, 300_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::NoWithVeto).unwrap();
+    suite.vote(CAROL, proposal_id, ProposalVoteOption::Abstain).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(proposal.veto_power, Uint128::new(400_000));
+    assert_eq!(suite.balance(SUBMITTER).u128(), REQUIRED_DEPOSIT);
+    assert_eq!(suite.balance(suite.governance.as_str()), Uint128::zero());
+}
+
+#[test]
+fn votes_can_be_changed_while_voting_is_open() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 300_000);
+    suite.stake(BOB, 200_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::For).unwrap();
+
+    let res = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            suite.governance.clone(),
+            &ExecuteMsg::CastVote {
+                proposal_id,
+                vote: ProposalVoteOption::Against,
+            },
+            &[],
+        )
+        .unwrap();
+    let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    let attribute = |key: &str| {
+        wasm.attributes
+            .iter()
+            .find(|a| a.key == key)
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(attribute("vote"), "against");
+    assert_eq!(attribute("previous_vote"), "for");
+
+    let votes: ProposalVotesResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.governance, &QueryMsg::ProposalVotes { proposal_id })
+        .unwrap();
+    assert_eq!(votes.for_power, Uint128::new(200_000));
+    assert_eq!(votes.against_power, Uint128::new(300_000));
+
+    let voters: Vec<Addr> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.governance,
+            &QueryMsg::ProposalVoters {
+                proposal_id,
+                vote_option: ProposalVoteOption::For,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(voters, vec![Addr::unchecked(BOB)]);
+
+    let vote: Option<ProposalVote> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.governance,
+            &QueryMsg::Vote {
+                proposal_id,
+                voter: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        vote,
+        Some(ProposalVote::single(
+            ProposalVoteOption::Against,
+            Uint128::new(300_000)
+        ))
+    );
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Rejected);
+}
+
+#[test]
+fn voters_are_paginated_by_address() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 300_000);
+    suite.stake(BOB, 200_000);
+    suite.stake(CAROL, 100_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(CAROL, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::Against).unwrap();
+
+    let first = suite.query_voters(proposal_id, ProposalVoteOption::For, None, Some(1));
+    assert_eq!(first, vec![Addr::unchecked(ALICE)]);
+    let rest = suite.query_voters(proposal_id, ProposalVoteOption::For, Some(ALICE), None);
+    assert_eq!(rest, vec![Addr::unchecked(CAROL)]);
+    let against = suite.query_voters(proposal_id, ProposalVoteOption::Against, None, None);
+    assert_eq!(against, vec![Addr::unchecked(BOB)]);
+}
+
+#[test]
+fn migration_keeps_votes() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 300_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    let code_id = suite.app.store_code(governance_contract());
+    suite
+        .app
+        .migrate_contract(
+            Addr::unchecked("owner"),
+            suite.governance.clone(),
+            &MigrateMsg {},
+            code_id,
+        )
+        .unwrap();
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.yes_power, Uint128::new(300_000));
+    let voters = suite.query_voters(proposal_id, ProposalVoteOption::For, None, None);
+    assert_eq!(voters, vec![Addr::unchecked(ALICE)]);
+    assert_eq!(
+        suite.query_by_status(ProposalStatus::Active, None, None),
+        vec![proposal_id]
+    );
+}
+
+#[test]
+fn migration_upgrades_baseline_state() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.stake(BOB, 300_000);
+    suite.advance_blocks(2);
+
+    let start_block = suite.app.block_info().height;
+    let baseline = BaselineState {
+        config: BaselineConfig {
+            staking_addr: suite.staking.clone(),
+            vesting_addr: Addr::unchecked("vesting"),
+            gov_token_denom: DENOM.to_string(),
+            proposal_voting_period: VOTING_PERIOD,
+            proposal_effective_delay: EFFECTIVE_DELAY,
+            proposal_expiration_period: EXPIRATION_PERIOD,
+            proposal_required_deposit: Uint128::new(REQUIRED_DEPOSIT),
+            proposal_required_quorum: Decimal::percent(10),
+            proposal_required_threshold: Decimal::percent(50),
+        },
+        proposals: vec![BaselineProposal {
+            proposal_id: Uint64::new(1),
+            submitter: Addr::unchecked(SUBMITTER),
+            status: ProposalStatus::Active,
+            yes_power: Uint128::new(600_000),
+            no_power: Uint128::new(300_000),
+            yes_voters: vec![Addr::unchecked(ALICE)],
+            no_voters: vec![Addr::unchecked(BOB)],
+            start_block,
+            start_time: suite.app.block_info().time.seconds(),
+            end_block: start_block + VOTING_PERIOD,
+            delayed_end_block: start_block + VOTING_PERIOD + EFFECTIVE_DELAY,
+            expiration_block: start_block + VOTING_PERIOD + EFFECTIVE_DELAY + EXPIRATION_PERIOD,
+            title: "Baseline proposal".to_string(),
+            description: "Stored before the migrations".to_string(),
+            link: None,
+            messages: None,
+            deposit_amount: Uint128::new(REQUIRED_DEPOSIT),
+        }],
+    };
+
+    let baseline_code_id = suite.app.store_code(baseline_governance_contract());
+    let governance = suite
+        .app
+        .instantiate_contract(
+            baseline_code_id,
+            Addr::unchecked("owner"),
+            &baseline,
+            &[],
+            "goblin-governance",
+            Some("owner".to_string()),
+        )
+        .unwrap();
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(SUBMITTER),
+            governance.clone(),
+            &coins(REQUIRED_DEPOSIT, DENOM),
+        )
+        .unwrap();
+
+    let code_id = suite.app.store_code(governance_contract());
+    suite
+        .app
+        .migrate_contract(Addr::unchecked("owner"), governance.clone(), &MigrateMsg {}, code_id)
+        .unwrap();
+    suite.governance = governance;
+
+    let config = suite.query_config();
+    assert_eq!(config.proposal_voting_period, Duration::Height(VOTING_PERIOD));
+    assert_eq!(config.proposal_effective_delay, Duration::Height(EFFECTIVE_DELAY));
+    assert_eq!(config.proposal_deposit_period, Duration::Height(VOTING_PERIOD));
+    assert_eq!(config.no_quorum_deposit_destination, DepositDestination::Refund);
+    assert_eq!(config.veto_deposit_destination, DepositDestination::Refund);
+    assert_eq!(config.guardian, None);
+
+    // The voters moved out of the proposal with their snapshot power
+    let voters = suite.query_voters(1, ProposalVoteOption::For, None, None);
+    assert_eq!(voters, vec![Addr::unchecked(ALICE)]);
+    let voters = suite.query_voters(1, ProposalVoteOption::Against, None, None);
+    assert_eq!(voters, vec![Addr::unchecked(BOB)]);
+    let proposal = suite.query_proposal(1);
+    assert_eq!(proposal.total_voting_power, Uint128::new(900_000));
+    assert_eq!(proposal.kind, ProposalKind::Text);
+    assert_eq!(
+        proposal.voting_end,
+        Expiration::AtHeight(start_block + VOTING_PERIOD + 1)
+    );
+    assert_eq!(suite.query_by_status(ProposalStatus::Active, None, None), vec![1]);
+
+    // The deposit recorded for the submitter is refunded once the proposal ends
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    let before = suite.balance(SUBMITTER);
+    suite.end_proposal(1).unwrap();
+    assert_eq!(suite.query_proposal(1).status, ProposalStatus::Passed);
+    assert_eq!(suite.balance(SUBMITTER), before + Uint128::new(REQUIRED_DEPOSIT));
+}
+
+#[test]
+fn weighted_votes_split_voting_power() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 300_000);
+    suite.stake(BOB, 200_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+
+    let weighted_vote = |options: Vec<(ProposalVoteOption, &str)>| ExecuteMsg::CastWeightedVote {
+        proposal_id,
+        options: options
+            .into_iter()
+            .map(|(option, weight)| (option, Decimal::from_str(weight).unwrap()))
+            .collect(),
+    };
+
+    for invalid in [
+        weighted_vote(vec![(ProposalVoteOption::For, "0.5"), (ProposalVoteOption::Against, "0.4")]),
+        weighted_vote(vec![(ProposalVoteOption::For, "0.5"), (ProposalVoteOption::For, "0.5")]),
+        weighted_vote(vec![(ProposalVoteOption::For, "1"), (ProposalVoteOption::Against, "0")]),
+        weighted_vote(vec![]),
+    ] {
+        let err = suite
+            .app
+            .execute_contract(Addr::unchecked(ALICE), suite.governance.clone(), &invalid, &[])
+            .unwrap_err();
+        assert_eq!(
+            err.root_cause().to_string(),
+            "Vote weights must be positive, unique per option and sum up to one!"
+        );
+    }
+
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(ALICE),
+            suite.governance.clone(),
+            &weighted_vote(vec![
+                (ProposalVoteOption::For, "0.7"),
+                (ProposalVoteOption::Against, "0.2"),
+                (ProposalVoteOption::NoWithVeto, "0.1"),
+            ]),
+            &[],
+        )
+        .unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::Abstain).unwrap();
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.yes_power, Uint128::new(210_000));
+    assert_eq!(proposal.no_power, Uint128::new(60_000));
+    assert_eq!(proposal.veto_power, Uint128::new(30_000));
+    assert_eq!(proposal.abstain_power, Uint128::new(200_000));
+
+    let voters = suite.query_voters(proposal_id, ProposalVoteOption::Against, None, None);
+    assert_eq!(voters, vec![Addr::unchecked(ALICE)]);
+
+    // Switching back to a single option takes the whole split back
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.yes_power, Uint128::new(300_000));
+    assert_eq!(proposal.no_power, Uint128::zero());
+    assert_eq!(proposal.veto_power, Uint128::zero());
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Passed);
+}
+
+#[test]
+fn quorum_uses_voting_power_captured_at_submission() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 100_000);
+    suite.advance_blocks(2);
+
+    let submitted_at = suite.app.block_info().height;
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.snapshot_height, submitted_at - 1);
+    assert_eq!(proposal.total_voting_power, Uint128::new(100_000));
+
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    // Stakes added after the submission neither vote nor dilute the quorum
+    suite.stake(BOB, 900_000);
+    suite.advance_blocks(2);
+    let err = suite.vote(BOB, proposal_id, ProposalVoteOption::Against).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "You don't have any voting power!");
+
+    let total: Uint128 = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.governance, &QueryMsg::TotalVotingPower { proposal_id })
+        .unwrap();
+    assert_eq!(total, Uint128::new(100_000));
+
+    suite.advance_blocks(VOTING_PERIOD);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Passed);
+}
+
+#[test]
+fn proposal_can_be_submitted_at_genesis() {
+    let mut suite = Suite::new();
+    suite.app.update_block(|b| b.height = 0);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.snapshot_height, 0);
+    assert_eq!(proposal.total_voting_power, Uint128::zero());
+}
+
+#[test]
+fn failing_proposal_message_marks_proposal_failed() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    // The governance contract holds no stake, so unstaking fails
+    let unstake = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: suite.staking.to_string(),
+        msg: to_binary(&StakingExecuteMsg::Unstake {
+            amount: Uint128::new(1),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let messages = vec![suite.update_threshold_msg("0.6"), unstake];
+    let proposal_id = suite.submit_proposal(ProposalKind::Custom { messages }).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(proposal_id).unwrap();
+
+    let result = suite.query_execution_result(proposal_id);
+    assert_eq!(result.status, ProposalStatus::Failed);
+    assert!(result.error.is_some());
+
+    // A failed proposal cannot be executed again
+    let err = suite.execute_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not passed!");
+}
+
+#[test]
+fn executed_proposal_reports_no_error() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let messages = vec![suite.update_threshold_msg("0.6")];
+    let proposal_id = suite.submit_proposal(ProposalKind::Custom { messages }).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(proposal_id).unwrap();
+
+    assert_eq!(
+        suite.query_execution_result(proposal_id),
+        ProposalExecutionResponse {
+            proposal_id,
+            status: ProposalStatus::Executed,
+            error: None,
+        }
+    );
+}
+
+#[test]
+fn submitter_cancels_proposal_before_first_vote() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let cancel = |proposal_id| ExecuteMsg::CancelProposal { proposal_id };
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    assert_eq!(suite.balance(SUBMITTER).u128(), REQUIRED_DEPOSIT);
+
+    let err = suite
+        .app
+        .execute_contract(Addr::unchecked(ALICE), suite.governance.clone(), &cancel(proposal_id), &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    suite
+        .app
+        .execute_contract(Addr::unchecked(SUBMITTER), suite.governance.clone(), &cancel(proposal_id), &[])
+        .unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Cancelled);
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+
+    let err = suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not active!");
+
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(CAROL),
+            suite.governance.clone(),
+            &ExecuteMsg::RemoveCompletedProposal { proposal_id },
+            &[],
+        )
+        .unwrap();
+
+    // Once voted on, the proposal has to run its course
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    let err = suite
+        .app
+        .execute_contract(Addr::unchecked(SUBMITTER), suite.governance.clone(), &cancel(proposal_id), &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal already has votes!");
+}
+
+#[test]
+fn unended_proposal_cannot_be_removed_after_expiration() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + EFFECTIVE_DELAY + EXPIRATION_PERIOD + 2);
+
+    let remove = ExecuteMsg::RemoveCompletedProposal { proposal_id };
+    let err = suite
+        .app
+        .execute_contract(Addr::unchecked(CAROL), suite.governance.clone(), &remove, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not completed!");
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Active);
+
+    // Ending it settles the deposit before it can be removed
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+    suite
+        .app
+        .execute_contract(Addr::unchecked(CAROL), suite.governance.clone(), &remove, &[])
+        .unwrap();
+}
+
+#[test]
+fn removed_proposal_leaves_no_votes_behind() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.stake(BOB, 300_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::Against).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Rejected);
+
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(CAROL),
+            suite.governance.clone(),
+            &ExecuteMsg::RemoveCompletedProposal { proposal_id },
+            &[],
+        )
+        .unwrap();
+
+    let vote: Option<ProposalVote> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.governance,
+            &QueryMsg::Vote {
+                proposal_id,
+                voter: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(vote, None);
+    assert!(suite
+        .query_voters(proposal_id, ProposalVoteOption::Against, None, None)
+        .is_empty());
+    assert!(suite
+        .query_voters(proposal_id, ProposalVoteOption::For, None, None)
+        .is_empty());
+}
+
+#[test]
+fn proposal_cannot_be_cancelled_after_voting_ended() {
+    let mut suite =
+        Suite::with_deposit_destinations(DepositDestination::Burn, DepositDestination::Burn);
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(SUBMITTER),
+            suite.governance.clone(),
+            &ExecuteMsg::CancelProposal { proposal_id },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Voting period ended!");
+
+    // Without a quorum the deposit is burned as configured
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Rejected);
+    assert_eq!(suite.balance(SUBMITTER).u128(), REQUIRED_DEPOSIT);
+}
+
+#[test]
+fn crowdfunded_deposit_starts_voting_and_is_refunded_per_depositor() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let submitter_deposit = REQUIRED_DEPOSIT / 4;
+    let proposal_id = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, submitter_deposit)
+        .unwrap();
+
+    suite.advance_blocks(10);
+    suite.add_deposit(DEPOSITOR, proposal_id, REQUIRED_DEPOSIT / 2).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Deposit);
+
+    suite.advance_blocks(10);
+    let started_at = suite.app.block_info().height;
+    suite.add_deposit(DEPOSITOR, proposal_id, REQUIRED_DEPOSIT / 2).unwrap();
+    assert_eq!(suite.balance(DEPOSITOR), Uint128::zero());
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Active);
+    assert_eq!(proposal.deposit_amount.u128(), submitter_deposit + REQUIRED_DEPOSIT);
+    assert_eq!(proposal.start_block, started_at);
+    assert_eq!(proposal.voting_end, Expiration::AtHeight(started_at + VOTING_PERIOD));
+    assert_eq!(proposal.total_voting_power, Uint128::new(600_000));
+
+    let err = suite.add_deposit(SUBMITTER, proposal_id, 1).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not in deposit period!");
+
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+    assert_eq!(suite.balance(DEPOSITOR).u128(), REQUIRED_DEPOSIT);
+}
+
+#[test]
+fn lapsed_deposit_period_refunds_depositors() {
+    let mut suite = Suite::new();
+
+    let proposal_id = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT / 4)
+        .unwrap();
+    suite.add_deposit(DEPOSITOR, proposal_id, REQUIRED_DEPOSIT / 4).unwrap();
+
+    let err = suite.end_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Deposit period not ended yet!");
+
+    suite.advance_blocks(DEPOSIT_PERIOD + 1);
+    let err = suite.add_deposit(DEPOSITOR, proposal_id, 1).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Deposit period ended!");
+
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Expired);
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+    assert_eq!(suite.balance(DEPOSITOR).u128(), REQUIRED_DEPOSIT);
+}
+
+#[test]
+fn deposit_of_proposal_without_quorum_goes_to_treasury() {
+    let mut suite = Suite::with_deposit_destinations(
+        DepositDestination::Treasury {
+            address: Addr::unchecked("treasury"),
+        },
+        DepositDestination::Burn,
+    );
+
+    suite.stake(ALICE, 50_000);
+    suite.stake(BOB, 950_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+
+    let res = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(CAROL),
+            suite.governance.clone(),
+            &ExecuteMsg::EndProposal { proposal_id },
+            &[],
+        )
+        .unwrap();
+    let event = res.events.iter().find(|e| e.ty == "wasm-proposal_deposit").unwrap();
+    let attribute = |key: &str| {
+        event
+            .attributes
+            .iter()
+            .find(|a| a.key == key)
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(attribute("destination"), "sent_to_treasury");
+    assert_eq!(attribute("treasury"), "treasury");
+    assert_eq!(attribute("amount"), REQUIRED_DEPOSIT.to_string());
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(
+        proposal.deposit_destination,
+        Some(DepositDestination::Treasury {
+            address: Addr::unchecked("treasury")
+        })
+    );
+    assert_eq!(suite.balance("treasury").u128(), REQUIRED_DEPOSIT);
+    assert_eq!(suite.balance(SUBMITTER).u128(), REQUIRED_DEPOSIT);
+}
+
+#[test]
+fn rejected_proposal_with_quorum_is_refunded() {
+    let mut suite = Suite::with_deposit_destinations(DepositDestination::Burn, DepositDestination::Burn);
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::Against).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Rejected);
+    assert_eq!(proposal.deposit_destination, Some(DepositDestination::Refund));
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+}
+
+fn threshold_update(threshold: &str) -> ProposalKind {
+    ProposalKind::UpdateGovConfig(Box::new(UpdateConfig {
+        proposal_voting_period: None,
+        proposal_effective_delay: None,
+        proposal_expiration_period: None,
+        proposal_required_deposit: None,
+        proposal_deposit_period: None,
+        proposal_min_initial_deposit: None,
+        proposal_required_quorum: None,
+        proposal_required_threshold: Some(threshold.to_string()),
+        proposal_veto_threshold: None,
+        expedited_voting_period: None,
+        expedited_required_deposit: None,
+        expedited_required_quorum: None,
+        expedited_required_threshold: None,
+        no_quorum_deposit_destination: None,
+        veto_deposit_destination: None,
+        guardian: None,
+        remove_guardian: None,
+        keeper_reward: None,
+    }))
+}
+
+#[test]
+fn typed_proposal_kinds_are_executed() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    // Fund the treasury held by the governance contract
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(DEPOSITOR),
+            suite.governance.clone(),
+            &coins(1_000, DENOM),
+        )
+        .unwrap();
+
+    let update_id = suite.submit_proposal(threshold_update("0.6")).unwrap();
+    let spend = ProposalKind::TreasurySpend {
+        recipient: CAROL.to_string(),
+        amount: coins(1_000, DENOM),
+    };
+    let spend_id = suite.submit_proposal(spend.clone()).unwrap();
+    assert_eq!(suite.query_proposal(spend_id).kind, spend);
+
+    for proposal_id in [update_id, spend_id] {
+        suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    }
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    for proposal_id in [update_id, spend_id] {
+        suite.end_proposal(proposal_id).unwrap();
+    }
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    for proposal_id in [update_id, spend_id] {
+        suite.execute_proposal(proposal_id).unwrap();
+        assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Executed);
+    }
+
+    assert_eq!(
+        suite.query_config().proposal_required_threshold,
+        Decimal::percent(60)
+    );
+    assert_eq!(suite.balance(CAROL).u128(), 1_001_000);
+}
+
+#[test]
+fn invalid_proposal_kinds_are_rejected_on_submission() {
+    let mut suite = Suite::new();
+
+    for (kind, error) in [
+        (
+            threshold_update("0.1"),
+            "Generic error: The required threshold for a proposal cannot be lower than 33% or higher than 100%",
+        ),
+        (
+            ProposalKind::Custom { messages: vec![] },
+            "Generic error: Custom proposal needs at least one message!",
+        ),
+        (
+            ProposalKind::TreasurySpend {
+                recipient: CAROL.to_string(),
+                amount: coins(0, DENOM),
+            },
+            "Generic error: Treasury spend needs a non-zero amount of every coin!",
+        ),
+        (
+            ProposalKind::UpdateStakingConfig(Box::new(StakingUpdateConfig {
+                lock_period: None,
+                max_claims: None,
+                early_exit_penalty: Some(Decimal::percent(200)),
+                penalty_destination: None,
+                reward_denoms: None,
+            })),
+            "Generic error: The early exit penalty cannot be higher than 100%",
+        ),
+        (
+            ProposalKind::UpdateStakingConfig(Box::new(StakingUpdateConfig {
+                lock_period: None,
+                max_claims: Some(0),
+                early_exit_penalty: None,
+                penalty_destination: None,
+                reward_denoms: None,
+            })),
+            "Generic error: At least one claim per address has to be allowed",
+        ),
+        (
+            ProposalKind::UpdateStakingConfig(Box::new(StakingUpdateConfig {
+                lock_period: None,
+                max_claims: None,
+                early_exit_penalty: None,
+                penalty_destination: None,
+                reward_denoms: Some(vec![DENOM.to_string(), DENOM.to_string()]),
+            })),
+            "Generic error: Reward denoms cannot be listed twice",
+        ),
+    ] {
+        let err = suite.submit_proposal(kind).unwrap_err();
+        assert_eq!(err.root_cause().to_string(), error);
+    }
+}
+
+#[test]
+fn expedited_proposal_passes_after_short_voting_period() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 700_000);
+    suite.stake(BOB, 300_000);
+    suite.advance_blocks(2);
+
+    // The regular deposit is not enough for an expedited proposal
+    let proposal_id = suite.submit_expedited_proposal(REQUIRED_DEPOSIT).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Deposit);
+    suite.add_deposit(DEPOSITOR, proposal_id, EXPEDITED_DEPOSIT - REQUIRED_DEPOSIT).unwrap();
+
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Active);
+    assert!(proposal.expedited);
+    assert_eq!(
+        proposal.voting_end,
+        Expiration::AtHeight(proposal.start_block + EXPEDITED_VOTING_PERIOD)
+    );
+
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::Against).unwrap();
+
+    suite.advance_blocks(EXPEDITED_VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Passed);
+}
+
+#[test]
+fn failed_expedited_proposal_falls_back_to_regular() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.stake(BOB, 400_000);
+    suite.advance_blocks(2);
+
+    let proposal_id = suite.submit_expedited_proposal(EXPEDITED_DEPOSIT).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.vote(BOB, proposal_id, ProposalVoteOption::Against).unwrap();
+
+    suite.advance_blocks(EXPEDITED_VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+
+    // 60% misses the expedited threshold, the proposal keeps running as a regular one
+    let proposal = suite.query_proposal(proposal_id);
+    assert_eq!(proposal.status, ProposalStatus::Active);
+    assert!(!proposal.expedited);
+    assert_eq!(
+        proposal.voting_end,
+        Expiration::AtHeight(proposal.start_block + VOTING_PERIOD)
+    );
+    assert_eq!(proposal.yes_power, Uint128::new(600_000));
+
+    let err = suite.end_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Voting period not ended yet!");
+
+    suite.advance_blocks(VOTING_PERIOD - EXPEDITED_VOTING_PERIOD);
+    suite.end_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Passed);
+    assert_eq!(suite.balance(SUBMITTER).u128(), 2 * REQUIRED_DEPOSIT);
+}
+
+#[test]
+fn guardian_vetoes_passed_proposal_during_delay() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let veto = |proposal_id| ExecuteMsg::VetoProposal { proposal_id };
+
+    let proposal_id = suite.submit_proposal(threshold_update("0.6")).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    let err = suite
+        .app
+        .execute_contract(Addr::unchecked(GUARDIAN), suite.governance.clone(), &veto(proposal_id), &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not passed!");
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+
+    let err = suite
+        .app
+        .execute_contract(Addr::unchecked(ALICE), suite.governance.clone(), &veto(proposal_id), &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    suite
+        .app
+        .execute_contract(Addr::unchecked(GUARDIAN), suite.governance.clone(), &veto(proposal_id), &[])
+        .unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Vetoed);
+
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    let err = suite.execute_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Proposal not passed!");
+    assert_eq!(
+        suite.query_config().proposal_required_threshold,
+        Decimal::percent(50)
+    );
+}
+
+#[test]
+fn guardian_is_removed_by_governance_only() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let remove_guardian = ProposalKind::UpdateGovConfig(Box::new(UpdateConfig {
+        proposal_voting_period: None,
+        proposal_effective_delay: None,
+        proposal_expiration_period: None,
+        proposal_required_deposit: None,
+        proposal_deposit_period: None,
+        proposal_min_initial_deposit: None,
+        proposal_required_quorum: None,
+        proposal_required_threshold: None,
+        proposal_veto_threshold: None,
+        expedited_voting_period: None,
+        expedited_required_deposit: None,
+        expedited_required_quorum: None,
+        expedited_required_threshold: None,
+        no_quorum_deposit_destination: None,
+        veto_deposit_destination: None,
+        guardian: None,
+        remove_guardian: Some(true),
+        keeper_reward: None,
+    }));
+
+    let ProposalKind::UpdateGovConfig(update) = remove_guardian.clone() else {
+        unreachable!()
+    };
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(GUARDIAN),
+            suite.governance.clone(),
+            &ExecuteMsg::UpdateConfig(update),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    let proposal_id = suite.submit_proposal(remove_guardian).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_config().guardian, None);
+
+    // Without a guardian passed proposals can no longer be vetoed
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(proposal_id).unwrap();
+    let err = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(GUARDIAN),
+            suite.governance.clone(),
+            &ExecuteMsg::VetoProposal { proposal_id },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+}
+
+fn periods_update(
+    voting_period: Duration,
+    effective_delay: Duration,
+    expiration_period: Duration,
+    deposit_period: Duration,
+    expedited_voting_period: Duration,
+) -> ProposalKind {
+    ProposalKind::UpdateGovConfig(Box::new(UpdateConfig {
+        proposal_voting_period: Some(voting_period),
+        proposal_effective_delay: Some(effective_delay),
+        proposal_expiration_period: Some(expiration_period),
+        proposal_required_deposit: None,
+        proposal_deposit_period: Some(deposit_period),
+        proposal_min_initial_deposit: None,
+        proposal_required_quorum: None,
+        proposal_required_threshold: None,
+        proposal_veto_threshold: None,
+        expedited_voting_period: Some(expedited_voting_period),
+        expedited_required_deposit: None,
+        expedited_required_quorum: None,
+        expedited_required_threshold: None,
+        no_quorum_deposit_destination: None,
+        veto_deposit_destination: None,
+        guardian: None,
+        remove_guardian: None,
+        keeper_reward: None,
+    }))
+}
+
+#[test]
+fn proposal_periods_can_be_time_based() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    // Periods have to be counted the same way so deadlines can be chained
+    let mixed = periods_update(
+        Duration::Time(86400),
+        Duration::Height(EFFECTIVE_DELAY),
+        Duration::Time(86400),
+        Duration::Time(86400),
+        Duration::Time(12000),
+    );
+    let err = suite.submit_proposal(mixed).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: All proposal periods have to be either block heights or times"
+    );
+
+    let to_time = periods_update(
+        Duration::Time(86400),
+        Duration::Time(43200),
+        Duration::Time(86400),
+        Duration::Time(86400),
+        Duration::Time(12000),
+    );
+    let proposal_id = suite.submit_proposal(to_time).unwrap();
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+    suite.advance_blocks(VOTING_PERIOD);
+    suite.end_proposal(proposal_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_config().proposal_voting_period, Duration::Time(86400));
+
+    let proposal_id = suite.submit_proposal(ProposalKind::Text).unwrap();
+    let proposal = suite.query_proposal(proposal_id);
+    let start = Timestamp::from_seconds(proposal.start_time);
+    assert_eq!(proposal.voting_end, Expiration::AtTime(start.plus_seconds(86400)));
+    assert_eq!(proposal.delay_end, Expiration::AtTime(start.plus_seconds(86400 + 43200)));
+    assert_eq!(
+        proposal.expiration,
+        Expiration::AtTime(start.plus_seconds(86400 + 43200 + 86400))
+    );
+
+    suite.vote(ALICE, proposal_id, ProposalVoteOption::For).unwrap();
+
+    // Only the block time counts, the height barely moves
+    suite.advance_time(86399);
+    let err = suite.end_proposal(proposal_id).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Voting period not ended yet!");
+    suite.advance_time(1);
+    suite.end_proposal(proposal_id).unwrap();
+
+    suite.advance_time(43200);
+    suite.execute_proposal(proposal_id).unwrap();
+    assert_eq!(suite.query_proposal(proposal_id).status, ProposalStatus::Executed);
+}
+
+#[test]
+fn crank_ends_and_executes_due_proposals() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+    suite.fund_keeper_pool(CAROL, KEEPER_REWARD + 500);
+    assert_eq!(suite.query_keeper_pool(), Uint128::new(KEEPER_REWARD + 500));
+
+    let msg = suite.update_threshold_msg("0.6");
+    let passing = suite
+        .submit_proposal(ProposalKind::Custom { messages: vec![msg] })
+        .unwrap();
+    let underfunded = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT / 2)
+        .unwrap();
+    suite.vote(ALICE, passing, ProposalVoteOption::For).unwrap();
+
+    // Nothing is due yet and nothing is paid
+    assert_eq!(suite.crank(None), 0);
+    assert_eq!(suite.balance(KEEPER), Uint128::zero());
+
+    // Ending proposals that get their deposits refunded earns nothing
+    suite.advance_blocks(VOTING_PERIOD);
+    assert_eq!(suite.crank(None), 2);
+    assert_eq!(suite.query_proposal(passing).status, ProposalStatus::Passed);
+    assert_eq!(suite.query_proposal(underfunded).status, ProposalStatus::Expired);
+    assert_eq!(suite.balance(KEEPER), Uint128::zero());
+
+    // The pass is not executable before the delay ends
+    assert_eq!(suite.crank(None), 0);
+
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    assert_eq!(suite.crank(None), 1);
+    assert_eq!(suite.query_proposal(passing).status, ProposalStatus::Executed);
+    assert_eq!(
+        suite.query_config().proposal_required_threshold,
+        Decimal::from_str("0.6").unwrap()
+    );
+    assert_eq!(suite.balance(KEEPER), Uint128::new(KEEPER_REWARD));
+    assert_eq!(suite.query_keeper_pool(), Uint128::new(500));
+}
+
+#[test]
+fn crank_rewards_only_proposals_losing_their_deposit() {
+    let mut suite =
+        Suite::with_deposit_destinations(DepositDestination::Refund, DepositDestination::Burn);
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+    suite.fund_keeper_pool(CAROL, KEEPER_REWARD + 500);
+
+    // Fully funded proposals nobody votes on come back to the submitter for free
+    let ignored = suite.submit_proposal(ProposalKind::Text).unwrap();
+    let vetoed = suite.submit_proposal(ProposalKind::Text).unwrap();
+    suite.vote(ALICE, vetoed, ProposalVoteOption::NoWithVeto).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD);
+    assert_eq!(suite.crank(None), 2);
+    assert_eq!(suite.query_proposal(ignored).status, ProposalStatus::Rejected);
+    assert_eq!(suite.query_proposal(vetoed).status, ProposalStatus::Rejected);
+    assert_eq!(suite.balance(SUBMITTER).u128(), REQUIRED_DEPOSIT);
+
+    // Only the burned deposit pays the keeper
+    assert_eq!(suite.balance(KEEPER), Uint128::new(KEEPER_REWARD));
+    assert_eq!(suite.query_keeper_pool(), Uint128::new(500));
+}
+
+#[test]
+fn crank_expires_passed_proposals_up_to_limit() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let first = suite.submit_proposal(ProposalKind::Text).unwrap();
+    let second = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT)
+        .unwrap();
+    suite.vote(ALICE, first, ProposalVoteOption::For).unwrap();
+    suite.vote(ALICE, second, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD);
+    assert_eq!(suite.crank(Some(1)), 1);
+    assert_eq!(suite.query_proposal(first).status, ProposalStatus::Passed);
+    assert_eq!(suite.query_proposal(second).status, ProposalStatus::Active);
+
+    assert_eq!(suite.crank(Some(1)), 1);
+    assert_eq!(suite.query_proposal(second).status, ProposalStatus::Passed);
+
+    suite.advance_blocks(EFFECTIVE_DELAY + EXPIRATION_PERIOD);
+    assert_eq!(suite.crank(None), 2);
+    assert_eq!(suite.query_proposal(first).status, ProposalStatus::Expired);
+    assert_eq!(suite.query_proposal(second).status, ProposalStatus::Expired);
+
+    // An empty pool pays nothing
+    assert_eq!(suite.balance(KEEPER), Uint128::zero());
+}
+
+#[test]
+fn proposals_can_be_listed_by_status_and_submitter() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let first = suite.submit_proposal(ProposalKind::Text).unwrap();
+    let underfunded = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT / 2)
+        .unwrap();
+    let third = suite
+        .submit_from(DEPOSITOR, ProposalKind::Text, REQUIRED_DEPOSIT, false)
+        .unwrap();
+
+    assert_eq!(
+        suite.query_by_status(ProposalStatus::Active, None, None),
+        vec![first, third]
+    );
+    assert_eq!(
+        suite.query_by_status(ProposalStatus::Active, None, Some(OrderBy::Desc)),
+        vec![third, first]
+    );
+    assert_eq!(
+        suite.query_by_status(ProposalStatus::Active, Some(third), Some(OrderBy::Desc)),
+        vec![first]
+    );
+    assert_eq!(
+        suite.query_by_status(ProposalStatus::Deposit, None, None),
+        vec![underfunded]
+    );
+
+    assert_eq!(suite.query_by_submitter(SUBMITTER, None), vec![first, underfunded]);
+    assert_eq!(
+        suite.query_by_submitter(SUBMITTER, Some(OrderBy::Desc)),
+        vec![underfunded, first]
+    );
+    assert_eq!(suite.query_by_submitter(DEPOSITOR, None), vec![third]);
+
+    // The status index follows the proposal through its life
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(SUBMITTER),
+            suite.governance.clone(),
+            &ExecuteMsg::CancelProposal { proposal_id: first },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(suite.query_by_status(ProposalStatus::Active, None, None), vec![third]);
+    assert_eq!(
+        suite.query_by_status(ProposalStatus::Cancelled, None, None),
+        vec![first]
+    );
+
+    let all = suite.query_proposal_ids(QueryMsg::Proposals {
+        start: Some(underfunded),
+        limit: None,
+        order: Some(OrderBy::Desc),
+    });
+    assert_eq!(all, vec![underfunded, first]);
+}
+
+#[test]
+fn crank_pays_nothing_for_lapsed_deposits() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+    suite.fund_keeper_pool(CAROL, 10 * KEEPER_REWARD);
+
+    // A single unit is not enough to open a proposal
+    let err = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, 1)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Insufficient token deposit!");
+
+    // Proposals opened with the minimum deposit only ever get refunded
+    for _ in 0..5 {
+        suite
+            .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT / 4)
+            .unwrap();
+    }
+
+    suite.advance_blocks(DEPOSIT_PERIOD);
+    assert_eq!(suite.crank(None), 5);
+    assert_eq!(suite.balance(KEEPER), Uint128::zero());
+    assert_eq!(suite.query_keeper_pool(), Uint128::new(10 * KEEPER_REWARD));
+}
+
+#[test]
+fn min_initial_deposit_is_validated() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    let ProposalKind::UpdateGovConfig(mut update) = threshold_update("0.5") else {
+        unreachable!()
+    };
+    update.proposal_min_initial_deposit = Some("0.05".to_string());
+    let err = suite
+        .submit_proposal(ProposalKind::UpdateGovConfig(update))
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: The minimum initial deposit for a proposal cannot be lower than 10% or higher than 100% of the required deposit"
+    );
+
+    // The share applies to the expedited deposit as well
+    let err = suite
+        .submit_expedited_proposal(EXPEDITED_DEPOSIT / 4 - 1)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Insufficient token deposit!");
+    suite.submit_expedited_proposal(EXPEDITED_DEPOSIT / 4).unwrap();
+}
+
+#[test]
+fn treasury_spends_leave_escrowed_funds_alone() {
+    let mut suite = Suite::new();
+
+    suite.stake(ALICE, 600_000);
+    suite.advance_blocks(2);
+
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(DEPOSITOR),
+            suite.governance.clone(),
+            &coins(1_000, DENOM),
+        )
+        .unwrap();
+    suite.fund_keeper_pool(CAROL, 500);
+
+    let spend = |amount| ProposalKind::TreasurySpend {
+        recipient: CAROL.to_string(),
+        amount: coins(amount, DENOM),
+    };
+    let overspend_id = suite.submit_proposal(spend(1_001)).unwrap();
+    let open_id = suite
+        .submit_proposal_with_deposit(ProposalKind::Text, REQUIRED_DEPOSIT / 2)
+        .unwrap();
+    suite.vote(ALICE, overspend_id, ProposalVoteOption::For).unwrap();
+
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(overspend_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+
+    // Only the treasury itself can be spent, not the open deposit or the keeper pool
+    let balance = suite.balance(CAROL);
+    suite.execute_proposal(overspend_id).unwrap();
+    assert_eq!(
+        suite.query_execution_result(overspend_id),
+        ProposalExecutionResponse {
+            proposal_id: overspend_id,
+            status: ProposalStatus::Failed,
+            error: Some("Proposal spends tokens held for deposits or keeper rewards!".to_string()),
+        }
+    );
+    assert_eq!(suite.balance(CAROL), balance);
+
+    let spend_id = suite.submit_proposal(spend(1_000)).unwrap();
+    suite.vote(ALICE, spend_id, ProposalVoteOption::For).unwrap();
+    suite.advance_blocks(VOTING_PERIOD + 1);
+    suite.end_proposal(spend_id).unwrap();
+    suite.advance_blocks(EFFECTIVE_DELAY);
+    suite.execute_proposal(spend_id).unwrap();
+    assert_eq!(suite.query_proposal(spend_id).status, ProposalStatus::Executed);
+    assert_eq!(suite.balance(CAROL), balance + Uint128::new(1_000));
+
+    // The open deposit is still there to be refunded
+    let balance = suite.balance(SUBMITTER);
+    suite.end_proposal(open_id).unwrap();
+    assert_eq!(suite.balance(SUBMITTER), balance + Uint128::new(REQUIRED_DEPOSIT / 2));
+    assert_eq!(suite.query_keeper_pool(), Uint128::new(500));
+}
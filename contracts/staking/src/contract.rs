@@ -1,33 +1,59 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    entry_point, Env, MessageInfo, 
-    Deps, DepsMut, StdResult, Response, QueryResponse, to_binary, Uint128, BankMsg, Coin, Addr
+    Env, MessageInfo,
+    Deps, DepsMut, StdResult, Response, QueryResponse, to_binary, from_binary, Uint128, BankMsg, Addr, Coin,
+    Order, Timestamp, Attribute, Event, Storage, attr,
 };
+use cw_storage_plus::Bound;
 use goblin_staking::state::{
     get_staking_amount_from_funds, insert_stake,
     remove_stake, add_claim, calculate_release_date,
-    remove_released_claims, get_staking_denom, get_partitioned_claims,
-    STAKES, TOTAL_STAKED, CONFIG,
+    remove_released_claims, get_staking_token, get_partitioned_claims,
+    get_total_staked_per_address_at, remove_locked_claims, get_claims_page, STAKES, TOTAL_STAKED, CONFIG, PENDING_OWNER,
 };
 use goblin_staking::query::{
-    QueryMsg, QueryClaimsResp, QueryStakersResp
+    QueryMsg, QueryClaimsResp, QueryStakersResp, StakerInfo, QueryPendingRewardsResp,
 };
-use goblin_staking::msg::{ExecuteMsg, InstantiateMsg};
+use goblin_staking::rewards::{
+    distribute_rewards, claim_rewards, get_pending_rewards,
+};
+use goblin_staking::delegation::{
+    delegate_votes, undelegate_votes, get_voting_power_at,
+};
+use goblin_staking::msg::{ExecuteMsg, InstantiateMsg, Cw20HookMsg};
+use goblin_staking::config::{StakingToken, PenaltyDestination, UpdateConfig};
+use cw20::Cw20ReceiveMsg;
 use goblin_staking::err::ContractError;
+use goblin_staking::events::*;
 
-#[entry_point]
+// Default pagination constants
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     
+    deps.api.addr_validate(msg.config.owner.as_str())?;
+    if let StakingToken::Cw20 { contract_addr } = &msg.config.token {
+        deps.api.addr_validate(contract_addr.as_str())?;
+    }
+    if let PenaltyDestination::Address { address } = &msg.config.penalty_destination {
+        deps.api.addr_validate(address.as_str())?;
+    }
+    msg.config.validate()?;
     CONFIG.save(deps.storage, &msg.config)?;
+    TOTAL_STAKED.save(deps.storage, &Uint128::zero(), env.block.height)?;
     Ok(Response::new())
 
 }
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -44,7 +70,37 @@ pub fn execute(
         },
         ExecuteMsg::Withdraw { } => {
             execute_withdraw(deps, env, info)
-        }
+        },
+        ExecuteMsg::UnstakeInstant { amount } => {
+            execute_unstake_instant(deps, env, info, amount)
+        },
+        ExecuteMsg::Rebond { amount } => {
+            execute_rebond(deps, env, info, amount)
+        },
+        ExecuteMsg::DistributeRewards { } => {
+            execute_distribute_rewards(deps, env, info)
+        },
+        ExecuteMsg::ClaimRewards { } => {
+            execute_claim_rewards(deps, env, info)
+        },
+        ExecuteMsg::Receive(msg) => {
+            execute_receive(deps, env, info, msg)
+        },
+        ExecuteMsg::UpdateConfig(config) => {
+            execute_update_config(deps, env, info, config)
+        },
+        ExecuteMsg::ProposeNewOwner { owner } => {
+            execute_propose_new_owner(deps, env, info, owner)
+        },
+        ExecuteMsg::AcceptOwnership {  } => {
+            execute_accept_ownership(deps, env, info)
+        },
+        ExecuteMsg::DelegateVotes { to } => {
+            execute_delegate_votes(deps, env, info, to)
+        },
+        ExecuteMsg::Undelegate {  } => {
+            execute_undelegate(deps, env, info)
+        },
     }
 
 }
@@ -55,12 +111,48 @@ pub fn execute_stake(
     info: MessageInfo,    
 ) -> Result<Response, ContractError>{
 
+    if let StakingToken::Cw20 { .. } = get_staking_token(deps.storage)? {
+        return Err(ContractError::InvalidToken{});
+    }
     let amount = get_staking_amount_from_funds(deps.storage, info.funds)?;
+    stake(deps, env, info.sender, amount)
+
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+
+    // only the configured cw20 contract may hand over tokens
+    match get_staking_token(deps.storage)? {
+        StakingToken::Cw20 { contract_addr } if contract_addr == info.sender => {},
+        _ => return Err(ContractError::InvalidToken{}),
+    }
+    let staker = deps.api.addr_validate(&msg.sender)?;
+    match from_binary(&msg.msg)? {
+        Cw20HookMsg::Stake {  } => stake(deps, env, staker, msg.amount),
+    }
+
+}
+
+fn stake(
+    deps: DepsMut,
+    env: Env,
+    staker: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
     if amount.is_zero() {
         return Err(ContractError::InsufficientFunds{});
     }
-    insert_stake(deps.storage, info.sender, amount, env.block.height)?;
-    Ok(Response::new())
+    insert_stake(deps.storage, staker.clone(), amount, env.block.height)?;
+
+    let mut attributes = vec![attr(ATTR_AMOUNT, amount)];
+    attributes.extend(stake_attributes(deps.storage, staker.clone())?);
+    Ok(action_response(ACTION_STAKE, &staker, attributes))
 
 }
 
@@ -71,10 +163,94 @@ pub fn execute_unstake(
     amount: Uint128,
 ) -> Result<Response, ContractError>{
 
+    // a zero claim would still take up one of the claim slots
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds{});
+    }
+
     let release_date = calculate_release_date(deps.storage, env.block.time)?;
     remove_stake(deps.storage, info.sender.clone(), amount, env.block.height)?;
-    add_claim(deps.storage, info.sender, amount, release_date)?;
-    Ok(Response::new())
+    add_claim(deps.storage, info.sender.clone(), amount, release_date)?;
+
+    let mut attributes = vec![attr(ATTR_AMOUNT, amount)];
+    attributes.extend(stake_attributes(deps.storage, info.sender.clone())?);
+    attributes.push(attr(ATTR_RELEASE_TIME, release_date.seconds().to_string()));
+    Ok(action_response(ACTION_UNSTAKE, &info.sender, attributes))
+
+}
+
+pub fn execute_unstake_instant(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds{});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    remove_stake(deps.storage, info.sender.clone(), amount, env.block.height)?;
+
+    let penalty = amount * config.early_exit_penalty;
+    let payout = amount.checked_sub(penalty)?;
+
+    let mut attributes = vec![attr(ATTR_AMOUNT, amount), attr(ATTR_PENALTY, penalty)];
+    attributes.extend(stake_attributes(deps.storage, info.sender.clone())?);
+    let mut response = action_response(ACTION_UNSTAKE_INSTANT, &info.sender, attributes);
+    if !payout.is_zero() {
+        response = response.add_message(config.token.transfer_msg(&info.sender, payout)?);
+    }
+    if penalty.is_zero() {
+        return Ok(response);
+    }
+
+    match config.penalty_destination {
+        PenaltyDestination::Address { address } => {
+            response = response.add_message(config.token.transfer_msg(&address, penalty)?);
+        },
+        PenaltyDestination::Stakers => {
+            // with nobody left to share it, the penalty is burned instead
+            if TOTAL_STAKED.load(deps.storage)?.is_zero() {
+                response = response.add_message(config.token.burn_msg(penalty)?);
+            } else if let StakingToken::Native { denom } = config.token {
+                distribute_rewards(deps.storage, vec![Coin { denom, amount: penalty }])?;
+            }
+        },
+        PenaltyDestination::Burn => {
+            response = response.add_message(config.token.burn_msg(penalty)?);
+        },
+    }
+
+    Ok(response)
+
+}
+
+pub fn execute_rebond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientClaims{});
+    }
+    let rebonded = remove_locked_claims(
+        deps.storage,
+        info.sender.clone(),
+        amount,
+        env.block.time,
+    )?;
+    if rebonded < amount {
+        return Err(ContractError::InsufficientClaims{});
+    }
+    insert_stake(deps.storage, info.sender.clone(), amount, env.block.height)?;
+
+    let mut attributes = vec![attr(ATTR_AMOUNT, amount)];
+    attributes.extend(stake_attributes(deps.storage, info.sender.clone())?);
+    Ok(action_response(ACTION_REBOND, &info.sender, attributes))
 
 }
 
@@ -84,26 +260,249 @@ pub fn execute_withdraw(
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
 
-    let denom = get_staking_denom(deps.storage)?;
+    let token = get_staking_token(deps.storage)?;
     let release_amnt = remove_released_claims(
         deps.storage, 
         info.sender.clone(), 
         env.block.time
     )?;
 
+    let response = action_response(
+        ACTION_WITHDRAW,
+        &info.sender,
+        vec![attr(ATTR_AMOUNT, release_amnt)],
+    );
+
+    // nothing has been released yet, a zero coin
+    // transfer would be rejected by the bank module
+    if release_amnt.is_zero() {
+        return Ok(response);
+    }
+
+    let msg = token.transfer_msg(&info.sender, release_amnt)?;
+    
+    Ok(response.add_message(msg))
+
+}
+
+pub fn execute_distribute_rewards(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    if info.funds.iter().all(|c| c.amount.is_zero()) {
+        return Err(ContractError::InsufficientFunds{});
+    }
+    // every accepted denom adds to the cost of each stake change,
+    // so only the denoms listed by the owner are taken
+    let config = CONFIG.load(deps.storage)?;
+    if info.funds.iter().any(|c| !config.reward_denoms.contains(&c.denom)) {
+        return Err(ContractError::InvalidRewardDenom{});
+    }
+    if TOTAL_STAKED.load(deps.storage)?.is_zero() {
+        return Err(ContractError::NoStakers{});
+    }
+    let amount = coins_to_string(&info.funds);
+    distribute_rewards(deps.storage, info.funds)?;
+    Ok(action_response(
+        ACTION_DISTRIBUTE_REWARDS,
+        &info.sender,
+        vec![attr(ATTR_AMOUNT, amount)],
+    ))
+
+}
+
+pub fn execute_claim_rewards(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    let rewards = claim_rewards(deps.storage, info.sender.clone())?;
+    let response = action_response(
+        ACTION_CLAIM_REWARDS,
+        &info.sender,
+        vec![attr(ATTR_AMOUNT, coins_to_string(&rewards))],
+    );
+    if rewards.is_empty() {
+        return Ok(response);
+    }
+
     let msg = BankMsg::Send {
         to_address: info.sender.to_string(),
-        amount: vec![ Coin{
-            denom: denom,
-            amount: release_amnt,
-        }]
+        amount: rewards,
     };
-    
-    Ok(Response::new().add_message(msg))
+
+    Ok(response.add_message(msg))
+
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    updated_config: UpdateConfig,
+) -> Result<Response, ContractError> {
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // only the governance contract may change the parameters
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(lock_period) = updated_config.lock_period {
+        config.lock_period = lock_period;
+    }
+
+    if let Some(max_claims) = updated_config.max_claims {
+        config.max_claims = max_claims;
+    }
+
+    if let Some(early_exit_penalty) = updated_config.early_exit_penalty {
+        config.early_exit_penalty = early_exit_penalty;
+    }
+
+    if let Some(penalty_destination) = updated_config.penalty_destination {
+        if let PenaltyDestination::Address { address } = &penalty_destination {
+            deps.api.addr_validate(address.as_str())?;
+        }
+        config.penalty_destination = penalty_destination;
+    }
+
+    if let Some(reward_denoms) = updated_config.reward_denoms {
+        config.reward_denoms = reward_denoms;
+    }
+
+    config.validate()?;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(action_response(ACTION_UPDATE_CONFIG, &info.sender, vec![]))
+
+}
+
+pub fn execute_propose_new_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let new_owner = deps.api.addr_validate(&owner)?;
+    PENDING_OWNER.save(deps.storage, &new_owner)?;
+    Ok(action_response(
+        ACTION_PROPOSE_NEW_OWNER,
+        &info.sender,
+        vec![attr(ATTR_OWNER, new_owner)],
+    ))
+
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    let pending_owner = PENDING_OWNER.may_load(deps.storage)?;
+    if pending_owner != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.owner = info.sender.clone();
+        Ok(config)
+    })?;
+    PENDING_OWNER.remove(deps.storage);
+    Ok(action_response(
+        ACTION_ACCEPT_OWNERSHIP,
+        &info.sender,
+        vec![attr(ATTR_OWNER, &info.sender)],
+    ))
+
+}
+
+pub fn execute_delegate_votes(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+
+    let delegate = deps.api.addr_validate(&to)?;
+    delegate_votes(deps.storage, info.sender.clone(), delegate.clone(), env.block.height)?;
+    Ok(action_response(
+        ACTION_DELEGATE_VOTES,
+        &info.sender,
+        vec![attr(ATTR_DELEGATE, delegate)],
+    ))
+
+}
+
+pub fn execute_undelegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    undelegate_votes(deps.storage, info.sender.clone(), env.block.height)?;
+    Ok(action_response(ACTION_UNDELEGATE, &info.sender, vec![]))
 
 }
 
-#[entry_point]
+// report "action" on the default event and all
+// attributes on a dedicated `wasm-<action>` event
+fn action_response(
+    action: &str,
+    sender: &Addr,
+    attributes: Vec<Attribute>,
+) -> Response {
+
+    let event = Event::new(action)
+        .add_attribute(ATTR_ACTION, action)
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attributes(attributes);
+    Response::new()
+        .add_attribute(ATTR_ACTION, action)
+        .add_event(event)
+
+}
+
+// stake of "address" and the total stake after an action
+fn stake_attributes(
+    store: &dyn Storage,
+    address: Addr,
+) -> StdResult<Vec<Attribute>> {
+
+    let new_stake = STAKES.may_load(store, address)?.unwrap_or_default();
+    let total_staked = TOTAL_STAKED.load(store)?;
+    Ok(vec![
+        attr(ATTR_NEW_STAKE, new_stake),
+        attr(ATTR_TOTAL_STAKED, total_staked),
+    ])
+
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+
+    // attribute values must not be empty
+    if coins.is_empty() {
+        return "0".to_string();
+    }
+    coins
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(
     deps: Deps,
     env: Env,
@@ -111,49 +510,77 @@ pub fn query(
 ) -> StdResult<QueryResponse> {
 
     match msg {
-        QueryMsg::Stakers {  } => todo!(),
+        QueryMsg::Config {  } => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Stakers { start_after, limit, height } => query_stakers(deps, env, start_after, limit, height),
         QueryMsg::Staked { address } => query_staked_per_address(deps, env, address),
         QueryMsg::StakedAt { address, height } => query_staked_at_per_address(deps, env, address, height),
         QueryMsg::TotalStaked {  } => query_total_staked(deps, env),
         QueryMsg::TotalStakedAt { height } => query_total_staked_at(deps, env, height),
-        QueryMsg::Claims { address } => query_claims(deps, env, address),
+        QueryMsg::Claims { address, start_after, limit } => query_claims(deps, env, address, start_after, limit),
+        QueryMsg::PendingRewards { address } => query_pending_rewards(deps, env, address),
+        QueryMsg::VotingPowerAt { address, height } => query_voting_power_at(deps, env, address, height),
     }
 
 }
 
-pub fn query_all_stakers(
+pub fn query_stakers(
     deps: Deps,
     _env: Env,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    height: Option<u64>,
 ) -> StdResult<QueryResponse> {
 
-    let stakers = STAKES.keys(
-        deps.storage, 
-        None, 
-        None,
-        cosmwasm_std::Order::Descending,
-    ).filter_map(|result| {
-        match result {
-            Ok(addr) => Some(addr.to_string()),
-            Err(_) => None,
-        }
-    }).collect::<Vec<_>>();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    // scan at most `limit` entries so that pages full of
+    // zero-balance stakers cannot exhaust the query gas
+    let entries = STAKES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next_start_after = if entries.len() == limit {
+        entries.last().map(|(addr, _)| addr.clone())
+    } else {
+        None
+    };
+
+    let stakers = entries
+        .into_iter()
+        .filter(|(_, staked)| !staked.is_zero())
+        .map(|(address, staked)| {
+            let staked_at = height
+                .map(|h| get_total_staked_per_address_at(deps.storage, address.clone(), h))
+                .transpose()?;
+            Ok(StakerInfo {
+                address,
+                staked,
+                staked_at,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
 
     let resp = QueryStakersResp{
-        stakers: stakers,
+        stakers,
+        next_start_after,
     };
 
-    Ok(to_binary(&resp)?)
+    to_binary(&resp)
 
 }
 
 pub fn query_staked_per_address(
     deps: Deps,
-    env: Env,
+    _env: Env,
     address: Addr,
 ) -> StdResult<QueryResponse> {
 
-    let height = env.block.height;
-    return query_staked_at_per_address(deps, env, address, height)
+    // snapshots at the current height do not yet include
+    // the changes made within this block, load the latest value
+    let resp = STAKES.may_load(deps.storage, address)?.unwrap_or(Uint128::from(0u32));
+    to_binary(&resp)
 
 }
 
@@ -165,17 +592,17 @@ pub fn query_staked_at_per_address(
 ) -> StdResult<QueryResponse> {
 
     let resp = STAKES.may_load_at_height(deps.storage, address, height)?.unwrap_or(Uint128::from(0u32));
-    Ok(to_binary(&resp)?)
+    to_binary(&resp)
 
 }
 
 pub fn query_total_staked(
     deps: Deps,
-    env: Env,
+    _env: Env,
 ) -> StdResult<QueryResponse> {
 
-    let height = env.block.height;
-    return query_total_staked_at(deps, env, height)
+    let resp = TOTAL_STAKED.may_load(deps.storage)?.unwrap_or(Uint128::from(0u32));
+    to_binary(&resp)
 
 }
 
@@ -186,7 +613,7 @@ pub fn query_total_staked_at(
 ) -> StdResult<QueryResponse> {
 
     let resp = TOTAL_STAKED.may_load_at_height(deps.storage, height)?.unwrap_or(Uint128::from(0u32));
-    Ok(to_binary(&resp)?)
+    to_binary(&resp)
 
 }
 
@@ -194,26 +621,66 @@ pub fn query_claims(
     deps: Deps,
     env: Env,
     address: Addr,
+    start_after: Option<Timestamp>,
+    limit: Option<u32>,
 ) -> StdResult<QueryResponse> {
 
     let (released_claims, unreleased_claims) = get_partitioned_claims(
         deps.storage,
-        address, 
+        address.clone(), 
         env.block.time,
     )?;
 
     let total_unreleased = unreleased_claims.total()?;
     let total_released = released_claims.total()?;
     let total = total_unreleased.checked_add(total_released)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let page = get_claims_page(deps.storage, address, start_after, limit)?;
+    let next_start_after = if page.len() == limit {
+        page.last().map(|c| c.release)
+    } else {
+        None
+    };
+    let (released, locked) = page
+        .into_iter()
+        .partition(|c| c.is_released(env.block.time));
     
     let resp = QueryClaimsResp {
-        released: released_claims,
-        total_released: total_released,
-        locked: unreleased_claims,
+        released,
+        locked,
+        total_released,
         total_locked: total_unreleased,
-        total: total,
+        total,
+        next_start_after,
     };
     
-    Ok(to_binary(&resp)?)
+    to_binary(&resp)
+
+}
+
+pub fn query_pending_rewards(
+    deps: Deps,
+    _env: Env,
+    address: Addr,
+) -> StdResult<QueryResponse> {
+
+    let resp = QueryPendingRewardsResp {
+        rewards: get_pending_rewards(deps.storage, address)?,
+    };
+
+    to_binary(&resp)
+
+}
+
+pub fn query_voting_power_at(
+    deps: Deps,
+    _env: Env,
+    address: Addr,
+    height: u64,
+) -> StdResult<QueryResponse> {
+
+    let resp = get_voting_power_at(deps.storage, address, height)?;
+    to_binary(&resp)
 
 }
\ No newline at end of file
@@ -1 +1 @@
-pub mod contract;
\ No newline at end of file
+pub mod contract;
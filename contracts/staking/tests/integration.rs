@@ -0,0 +1,1184 @@
+use cosmwasm_std::{coin, coins, to_binary, Addr, Coin, Decimal, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use goblin_staking::config::{Config, PenaltyDestination, StakingToken, UpdateConfig};
+use goblin_staking::events::*;
+use goblin_staking::msg::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
+use goblin_staking::query::{QueryClaimsResp, QueryMsg, QueryPendingRewardsResp, QueryStakersResp};
+
+const DENOM: &str = "ugoblin";
+const LOCK_PERIOD: u64 = 86400;
+const REWARD_DENOM: &str = "uluna";
+const TREASURY: &str = "treasury";
+const OWNER: &str = "owner";
+
+fn staking_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        goblin_staking_contract::contract::execute,
+        goblin_staking_contract::contract::instantiate,
+        goblin_staking_contract::contract::query,
+    ))
+}
+
+fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+fn config(token: StakingToken) -> Config {
+    Config {
+        owner: Addr::unchecked(OWNER),
+        token,
+        lock_period: LOCK_PERIOD,
+        claim_epoch: 1,
+        max_claims: 10,
+        early_exit_penalty: Decimal::zero(),
+        penalty_destination: PenaltyDestination::Burn,
+        reward_denoms: vec![DENOM.to_string(), REWARD_DENOM.to_string()],
+    }
+}
+
+fn setup(stakers: &[(&str, u128)]) -> (App, Addr) {
+    setup_with_config(
+        stakers,
+        config(StakingToken::Native {
+            denom: DENOM.to_string(),
+        }),
+    )
+}
+
+fn setup_with_config(stakers: &[(&str, u128)], config: Config) -> (App, Addr) {
+    let mut app = App::new(|router, _, storage| {
+        for (staker, amount) in stakers {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(*staker), coins(*amount, DENOM))
+                .unwrap();
+        }
+        router
+            .bank
+            .init_balance(
+                storage,
+                &Addr::unchecked(TREASURY),
+                vec![coin(1_000_000, REWARD_DENOM), coin(1_000_000, DENOM)],
+            )
+            .unwrap();
+    });
+
+    let code_id = app.store_code(staking_contract());
+    let staking = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                config,
+            },
+            &[],
+            "goblin-staking",
+            None,
+        )
+        .unwrap();
+
+    (app, staking)
+}
+
+fn stake(app: &mut App, staking: &Addr, staker: &str, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        staking.clone(),
+        &ExecuteMsg::Stake {},
+        &coins(amount, DENOM),
+    )
+    .unwrap();
+}
+
+fn query_staked(app: &App, staking: &Addr, address: &str) -> Uint128 {
+    app.wrap()
+        .query_wasm_smart(
+            staking,
+            &QueryMsg::Staked {
+                address: Addr::unchecked(address),
+            },
+        )
+        .unwrap()
+}
+
+fn query_total_staked(app: &App, staking: &Addr) -> Uint128 {
+    app.wrap()
+        .query_wasm_smart(staking, &QueryMsg::TotalStaked {})
+        .unwrap()
+}
+
+#[test]
+fn stake_updates_balances_and_snapshots() {
+    let (mut app, staking) = setup(&[("alice", 1_000), ("bob", 500)]);
+    let stake_height = app.block_info().height;
+
+    stake(&mut app, &staking, "alice", 1_000);
+    stake(&mut app, &staking, "bob", 200);
+    app.update_block(|b| b.height += 1);
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(1_000));
+    assert_eq!(query_staked(&app, &staking, "bob"), Uint128::new(200));
+    assert_eq!(query_total_staked(&app, &staking), Uint128::new(1_200));
+
+    // the stake is not part of the snapshot taken at the block it was made in
+    let staked_before: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::StakedAt {
+                address: Addr::unchecked("alice"),
+                height: stake_height,
+            },
+        )
+        .unwrap();
+    assert_eq!(staked_before, Uint128::zero());
+
+    let total_before: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::TotalStakedAt {
+                height: stake_height,
+            },
+        )
+        .unwrap();
+    assert_eq!(total_before, Uint128::zero());
+}
+
+#[test]
+fn stake_without_funds_fails() {
+    let (mut app, staking) = setup(&[("alice", 1_000)]);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking,
+            &ExecuteMsg::Stake {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "insufficient funds");
+}
+
+#[test]
+fn unstake_and_withdraw_after_lock_period() {
+    let (mut app, staking) = setup(&[("alice", 1_000)]);
+
+    stake(&mut app, &staking, "alice", 1_000);
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(400),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(600));
+    assert_eq!(query_total_staked(&app, &staking), Uint128::new(600));
+
+    let claims: QueryClaimsResp = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::Claims {
+                address: Addr::unchecked("alice"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(claims.total_locked, Uint128::new(400));
+    assert_eq!(claims.total_released, Uint128::zero());
+
+    // nothing is released before the lock period passed
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance("alice", DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::zero());
+
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(LOCK_PERIOD);
+    });
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance("alice", DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(400));
+
+    let claims: QueryClaimsResp = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::Claims {
+                address: Addr::unchecked("alice"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(claims.total, Uint128::zero());
+}
+
+#[test]
+fn unstake_more_than_staked_fails() {
+    let (mut app, staking) = setup(&[("alice", 1_000)]);
+
+    stake(&mut app, &staking, "alice", 100);
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(101),
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(100));
+}
+
+#[test]
+fn zero_unstakes_fail() {
+    let (mut app, staking) = setup(&[("alice", 1_000)]);
+
+    stake(&mut app, &staking, "alice", 100);
+    for msg in [
+        ExecuteMsg::Unstake { amount: Uint128::zero() },
+        ExecuteMsg::UnstakeInstant { amount: Uint128::zero() },
+    ] {
+        let err = app
+            .execute_contract(Addr::unchecked("alice"), staking.clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(err.root_cause().to_string(), "insufficient funds");
+    }
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(100));
+    assert_eq!(query_claims(&app, &staking, "alice").locked.into_iter().count(), 0);
+}
+
+#[test]
+fn stakers_are_paginated_and_skip_empty_stakes() {
+    let (mut app, staking) = setup(&[
+        ("alice", 1_000),
+        ("bob", 1_000),
+        ("carol", 1_000),
+        ("dave", 1_000),
+        ("erin", 1_000),
+    ]);
+
+    stake(&mut app, &staking, "alice", 100);
+    stake(&mut app, &staking, "bob", 200);
+    stake(&mut app, &staking, "carol", 300);
+    stake(&mut app, &staking, "dave", 400);
+    stake(&mut app, &staking, "erin", 500);
+    let snapshot_height = app.block_info().height + 1;
+    app.update_block(|b| b.height += 1);
+
+    // bob leaves a zero-balance entry behind
+    app.execute_contract(
+        Addr::unchecked("bob"),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(200),
+        },
+        &[],
+    )
+    .unwrap();
+    stake(&mut app, &staking, "carol", 700);
+
+    let mut start_after = None;
+    let mut pages = vec![];
+    loop {
+        let resp: QueryStakersResp = app
+            .wrap()
+            .query_wasm_smart(
+                &staking,
+                &QueryMsg::Stakers {
+                    start_after,
+                    limit: Some(2),
+                    height: Some(snapshot_height),
+                },
+            )
+            .unwrap();
+        pages.push(resp.stakers);
+        match resp.next_start_after {
+            Some(next) => start_after = Some(next),
+            None => break,
+        }
+    }
+
+    let stakers = pages.concat();
+    let summary = stakers
+        .iter()
+        .map(|s| (s.address.as_str(), s.staked.u128(), s.staked_at.unwrap().u128()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("alice", 100, 100),
+            ("carol", 1_000, 300),
+            ("dave", 400, 400),
+            ("erin", 500, 500),
+        ]
+    );
+
+    let resp: QueryStakersResp = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::Stakers {
+                start_after: None,
+                limit: None,
+                height: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(resp.stakers.len(), 4);
+    assert_eq!(resp.stakers[0].staked_at, None);
+    assert_eq!(resp.next_start_after, None);
+}
+
+fn distribute(app: &mut App, staking: &Addr, funds: Vec<Coin>) -> anyhow::Result<()> {
+    app.execute_contract(
+        Addr::unchecked(TREASURY),
+        staking.clone(),
+        &ExecuteMsg::DistributeRewards {},
+        &funds,
+    )?;
+    Ok(())
+}
+
+fn query_pending_rewards(app: &App, staking: &Addr, address: &str) -> Vec<Coin> {
+    let resp: QueryPendingRewardsResp = app
+        .wrap()
+        .query_wasm_smart(
+            staking,
+            &QueryMsg::PendingRewards {
+                address: Addr::unchecked(address),
+            },
+        )
+        .unwrap();
+    resp.rewards
+}
+
+#[test]
+fn rewards_are_shared_by_stake() {
+    let (mut app, staking) = setup(&[("alice", 1_000), ("bob", 1_000), ("carol", 1_000)]);
+
+    // nobody to distribute to yet
+    let err = distribute(&mut app, &staking, coins(100, REWARD_DENOM)).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "no tokens are staked");
+
+    stake(&mut app, &staking, "alice", 300);
+    stake(&mut app, &staking, "bob", 100);
+    distribute(
+        &mut app,
+        &staking,
+        vec![coin(40, DENOM), coin(400, REWARD_DENOM)],
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "alice"),
+        vec![coin(30, DENOM), coin(300, REWARD_DENOM)]
+    );
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "bob"),
+        vec![coin(10, DENOM), coin(100, REWARD_DENOM)]
+    );
+
+    // late stakers only earn from later distributions
+    stake(&mut app, &staking, "carol", 400);
+    assert_eq!(query_pending_rewards(&app, &staking, "carol"), vec![]);
+
+    // unstaked tokens stop earning
+    app.execute_contract(
+        Addr::unchecked("bob"),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(100),
+        },
+        &[],
+    )
+    .unwrap();
+
+    distribute(&mut app, &staking, coins(700, REWARD_DENOM)).unwrap();
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "alice"),
+        vec![coin(30, DENOM), coin(600, REWARD_DENOM)]
+    );
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "bob"),
+        vec![coin(10, DENOM), coin(100, REWARD_DENOM)]
+    );
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "carol"),
+        vec![coin(400, REWARD_DENOM)]
+    );
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::ClaimRewards {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap().query_balance("alice", REWARD_DENOM).unwrap().amount,
+        Uint128::new(600)
+    );
+    assert_eq!(
+        app.wrap().query_balance("alice", DENOM).unwrap().amount,
+        Uint128::new(730)
+    );
+    assert_eq!(query_pending_rewards(&app, &staking, "alice"), vec![]);
+
+    // claiming twice pays nothing more
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::ClaimRewards {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap().query_balance("alice", REWARD_DENOM).unwrap().amount,
+        Uint128::new(600)
+    );
+
+    // the staked principal is untouched by the payouts
+    assert_eq!(query_total_staked(&app, &staking), Uint128::new(700));
+}
+
+fn setup_cw20(stakers: &[(&str, u128)]) -> (App, Addr, Addr) {
+    let mut app = App::default();
+
+    let cw20_code_id = app.store_code(cw20_contract());
+    let token = app
+        .instantiate_contract(
+            cw20_code_id,
+            Addr::unchecked(OWNER),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Goblin".to_string(),
+                symbol: "GOBLIN".to_string(),
+                decimals: 6,
+                initial_balances: stakers
+                    .iter()
+                    .map(|(address, amount)| Cw20Coin {
+                        address: address.to_string(),
+                        amount: Uint128::new(*amount),
+                    })
+                    .collect(),
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "goblin-token",
+            None,
+        )
+        .unwrap();
+
+    let code_id = app.store_code(staking_contract());
+    let staking = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                config: config(StakingToken::Cw20 {
+                    contract_addr: token.clone(),
+                }),
+            },
+            &[],
+            "goblin-staking",
+            None,
+        )
+        .unwrap();
+
+    (app, staking, token)
+}
+
+fn query_cw20_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let resp: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    resp.balance
+}
+
+#[test]
+fn cw20_stake_unstake_and_withdraw() {
+    let (mut app, staking, token) = setup_cw20(&[("alice", 1_000)]);
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: staking.to_string(),
+            amount: Uint128::new(700),
+            msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(700));
+    assert_eq!(query_cw20_balance(&app, &token, "alice"), Uint128::new(300));
+    assert_eq!(query_cw20_balance(&app, &token, staking.as_str()), Uint128::new(700));
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(200),
+        },
+        &[],
+    )
+    .unwrap();
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(LOCK_PERIOD);
+    });
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(500));
+    assert_eq!(query_cw20_balance(&app, &token, "alice"), Uint128::new(500));
+}
+
+#[test]
+fn cw20_staking_rejects_other_tokens() {
+    let (mut app, staking, _) = setup_cw20(&[("alice", 1_000)]);
+
+    // receive hooks are only accepted from the configured token
+    let err = app
+        .execute_contract(
+            Addr::unchecked("mallory"),
+            staking.clone(),
+            &ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: "mallory".to_string(),
+                amount: Uint128::new(1_000),
+                msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "token is not accepted for staking");
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking,
+            &ExecuteMsg::Stake {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "token is not accepted for staking");
+}
+
+fn native_config_with_penalty(penalty: u64, destination: PenaltyDestination) -> Config {
+    Config {
+        early_exit_penalty: Decimal::percent(penalty),
+        penalty_destination: destination,
+        ..config(StakingToken::Native {
+            denom: DENOM.to_string(),
+        })
+    }
+}
+
+fn unstake_instant(app: &mut App, staking: &Addr, staker: &str, amount: u128) {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        staking.clone(),
+        &ExecuteMsg::UnstakeInstant {
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn instant_unstake_sends_penalty_to_address() {
+    let (mut app, staking) = setup_with_config(
+        &[("alice", 1_000)],
+        native_config_with_penalty(
+            10,
+            PenaltyDestination::Address {
+                address: Addr::unchecked("goblin-treasury"),
+            },
+        ),
+    );
+
+    stake(&mut app, &staking, "alice", 1_000);
+    unstake_instant(&mut app, &staking, "alice", 500);
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(500));
+    assert_eq!(
+        app.wrap().query_balance("alice", DENOM).unwrap().amount,
+        Uint128::new(450)
+    );
+    assert_eq!(
+        app.wrap().query_balance("goblin-treasury", DENOM).unwrap().amount,
+        Uint128::new(50)
+    );
+
+    // no claim is left behind
+    let claims: QueryClaimsResp = app
+        .wrap()
+        .query_wasm_smart(
+            &staking,
+            &QueryMsg::Claims {
+                address: Addr::unchecked("alice"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(claims.total, Uint128::zero());
+}
+
+#[test]
+fn instant_unstake_penalty_goes_to_remaining_stakers() {
+    let (mut app, staking) = setup_with_config(
+        &[("alice", 1_000), ("bob", 1_000), ("carol", 1_000)],
+        native_config_with_penalty(20, PenaltyDestination::Stakers),
+    );
+
+    stake(&mut app, &staking, "alice", 1_000);
+    stake(&mut app, &staking, "bob", 300);
+    stake(&mut app, &staking, "carol", 100);
+    unstake_instant(&mut app, &staking, "alice", 1_000);
+
+    assert_eq!(
+        app.wrap().query_balance("alice", DENOM).unwrap().amount,
+        Uint128::new(800)
+    );
+    assert_eq!(query_pending_rewards(&app, &staking, "alice"), vec![]);
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "bob"),
+        vec![coin(150, DENOM)]
+    );
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "carol"),
+        vec![coin(50, DENOM)]
+    );
+}
+
+#[test]
+fn instant_unstake_burns_penalty() {
+    let (mut app, staking) = setup_with_config(
+        &[("alice", 1_000)],
+        native_config_with_penalty(25, PenaltyDestination::Burn),
+    );
+
+    stake(&mut app, &staking, "alice", 1_000);
+    unstake_instant(&mut app, &staking, "alice", 1_000);
+
+    assert_eq!(
+        app.wrap().query_balance("alice", DENOM).unwrap().amount,
+        Uint128::new(750)
+    );
+    assert_eq!(
+        app.wrap().query_balance(&staking, DENOM).unwrap().amount,
+        Uint128::zero()
+    );
+}
+
+#[test]
+fn penalty_is_only_updated_by_owner() {
+    let (mut app, staking) = setup(&[("alice", 1_000)]);
+
+    let update = ExecuteMsg::UpdateConfig(UpdateConfig {
+        lock_period: None,
+        max_claims: None,
+        early_exit_penalty: Some(Decimal::percent(5)),
+        penalty_destination: Some(PenaltyDestination::Stakers),
+        reward_denoms: None,
+    });
+
+    let err = app
+        .execute_contract(Addr::unchecked("alice"), staking.clone(), &update, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "unauthorized");
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            staking.clone(),
+            &ExecuteMsg::UpdateConfig(UpdateConfig {
+                lock_period: None,
+                max_claims: None,
+                early_exit_penalty: Some(Decimal::percent(101)),
+                penalty_destination: None,
+                reward_denoms: None,
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: The early exit penalty cannot be higher than 100%"
+    );
+
+    app.execute_contract(Addr::unchecked(OWNER), staking.clone(), &update, &[])
+        .unwrap();
+
+    stake(&mut app, &staking, "alice", 1_000);
+    unstake_instant(&mut app, &staking, "alice", 1_000);
+    assert_eq!(
+        app.wrap().query_balance("alice", DENOM).unwrap().amount,
+        Uint128::new(950)
+    );
+}
+
+fn query_claims(app: &App, staking: &Addr, address: &str) -> QueryClaimsResp {
+    app.wrap()
+        .query_wasm_smart(
+            staking,
+            &QueryMsg::Claims {
+                address: Addr::unchecked(address),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn rebond_consumes_newest_claims_first() {
+    let (mut app, staking) = setup(&[("alice", 1_000)]);
+
+    stake(&mut app, &staking, "alice", 1_000);
+    for amount in [100u128, 200, 300] {
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            staking.clone(),
+            &ExecuteMsg::Unstake {
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+        .unwrap();
+        app.update_block(|b| {
+            b.height += 1;
+            b.time = b.time.plus_seconds(LOCK_PERIOD / 3);
+        });
+    }
+    // the first claim has been released by now
+    let claims = query_claims(&app, &staking, "alice");
+    assert_eq!(claims.total_released, Uint128::new(100));
+    assert_eq!(claims.total_locked, Uint128::new(500));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking.clone(),
+            &ExecuteMsg::Rebond {
+                amount: Uint128::new(501),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "not enough unbonding tokens");
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Rebond {
+            amount: Uint128::new(350),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(query_staked(&app, &staking, "alice"), Uint128::new(750));
+    assert_eq!(query_total_staked(&app, &staking), Uint128::new(750));
+
+    // the 300 claim is gone and 50 of the 200 claim remain
+    let claims = query_claims(&app, &staking, "alice");
+    assert_eq!(claims.total_released, Uint128::new(100));
+    assert_eq!(claims.total_locked, Uint128::new(150));
+    assert_eq!(claims.locked.into_iter().map(|c| c.amount.u128()).collect::<Vec<_>>(), vec![150]);
+}
+
+fn unstake(app: &mut App, staking: &Addr, staker: &str, amount: u128) -> anyhow::Result<()> {
+    app.execute_contract(
+        Addr::unchecked(staker),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(amount),
+        },
+        &[],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn claims_are_merged_per_epoch_and_capped() {
+    const EPOCH: u64 = 3600;
+    let (mut app, staking) = setup_with_config(
+        &[("alice", 1_000)],
+        Config {
+            claim_epoch: EPOCH,
+            max_claims: 3,
+            ..config(StakingToken::Native {
+                denom: DENOM.to_string(),
+            })
+        },
+    );
+    // start at the beginning of an epoch
+    app.update_block(|b| b.time = b.time.minus_seconds(b.time.seconds() % EPOCH));
+    let start = app.block_info().time;
+
+    stake(&mut app, &staking, "alice", 1_000);
+    for _ in 0..4 {
+        unstake(&mut app, &staking, "alice", 10).unwrap();
+        app.update_block(|b| b.time = b.time.plus_seconds(EPOCH / 4));
+    }
+    // four unstakes within the same epoch end up in one claim
+    let claims = query_claims(&app, &staking, "alice");
+    assert_eq!(claims.locked.clone().into_iter().count(), 1);
+    assert_eq!(claims.total_locked, Uint128::new(40));
+    let claim = claims.locked.into_iter().next().unwrap();
+    assert_eq!(claim.release, start.plus_seconds(LOCK_PERIOD + 3 * EPOCH / 4));
+
+    unstake(&mut app, &staking, "alice", 10).unwrap();
+    app.update_block(|b| b.time = b.time.plus_seconds(EPOCH));
+    unstake(&mut app, &staking, "alice", 10).unwrap();
+    app.update_block(|b| b.time = b.time.plus_seconds(EPOCH));
+
+    let err = unstake(&mut app, &staking, "alice", 10).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "too many outstanding claims, withdraw or rebond first"
+    );
+
+    // walk the claims page by page
+    let mut start_after = None;
+    let mut amounts = vec![];
+    loop {
+        let resp: QueryClaimsResp = app
+            .wrap()
+            .query_wasm_smart(
+                &staking,
+                &QueryMsg::Claims {
+                    address: Addr::unchecked("alice"),
+                    start_after,
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        assert_eq!(resp.total, Uint128::new(60));
+        amounts.extend(resp.locked.into_iter().map(|c| c.amount.u128()));
+        match resp.next_start_after {
+            Some(next) => start_after = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(amounts, vec![40, 10, 10]);
+
+    // withdrawing the released claims frees up room again
+    app.update_block(|b| b.time = b.time.plus_seconds(LOCK_PERIOD));
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap().query_balance("alice", DENOM).unwrap().amount,
+        Uint128::new(60)
+    );
+    unstake(&mut app, &staking, "alice", 10).unwrap();
+}
+
+fn query_config(app: &App, staking: &Addr) -> Config {
+    app.wrap()
+        .query_wasm_smart(staking, &QueryMsg::Config {})
+        .unwrap()
+}
+
+#[test]
+fn ownership_is_transferred_in_two_steps() {
+    let (mut app, staking) = setup(&[]);
+
+    let propose = ExecuteMsg::ProposeNewOwner {
+        owner: "governance".to_string(),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked("mallory"), staking.clone(), &propose, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "unauthorized");
+
+    app.execute_contract(Addr::unchecked(OWNER), staking.clone(), &propose, &[])
+        .unwrap();
+
+    // only the proposed owner can accept
+    let err = app
+        .execute_contract(
+            Addr::unchecked("mallory"),
+            staking.clone(),
+            &ExecuteMsg::AcceptOwnership {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "unauthorized");
+    assert_eq!(query_config(&app, &staking).owner, Addr::unchecked(OWNER));
+
+    app.execute_contract(
+        Addr::unchecked("governance"),
+        staking.clone(),
+        &ExecuteMsg::AcceptOwnership {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_config(&app, &staking).owner, Addr::unchecked("governance"));
+
+    // the previous owner lost its rights
+    let update = ExecuteMsg::UpdateConfig(UpdateConfig {
+        lock_period: Some(3 * LOCK_PERIOD),
+        max_claims: Some(20),
+        early_exit_penalty: None,
+        penalty_destination: None,
+        reward_denoms: None,
+    });
+    let err = app
+        .execute_contract(Addr::unchecked(OWNER), staking.clone(), &update, &[])
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "unauthorized");
+
+    app.execute_contract(Addr::unchecked("governance"), staking.clone(), &update, &[])
+        .unwrap();
+    let config = query_config(&app, &staking);
+    assert_eq!(config.lock_period, 3 * LOCK_PERIOD);
+    assert_eq!(config.max_claims, 20);
+}
+
+fn query_voting_power_at(app: &App, staking: &Addr, address: &str, height: u64) -> Uint128 {
+    app.wrap()
+        .query_wasm_smart(
+            staking,
+            &QueryMsg::VotingPowerAt {
+                address: Addr::unchecked(address),
+                height,
+            },
+        )
+        .unwrap()
+}
+
+fn delegate(app: &mut App, staking: &Addr, delegator: &str, to: &str) -> anyhow::Result<()> {
+    app.execute_contract(
+        Addr::unchecked(delegator),
+        staking.clone(),
+        &ExecuteMsg::DelegateVotes { to: to.to_string() },
+        &[],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn delegated_voting_power_is_snapshotted() {
+    let (mut app, staking) = setup(&[("alice", 1_000), ("bob", 1_000), ("carol", 1_000)]);
+
+    stake(&mut app, &staking, "alice", 500);
+    stake(&mut app, &staking, "bob", 200);
+    app.update_block(|b| b.height += 1);
+    let before_delegation = app.block_info().height;
+
+    let err = delegate(&mut app, &staking, "alice", "alice").unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "cannot delegate votes to yourself");
+
+    delegate(&mut app, &staking, "alice", "bob").unwrap();
+    // stake changes of the delegator follow the delegation
+    stake(&mut app, &staking, "alice", 100);
+    app.update_block(|b| b.height += 1);
+    let after_delegation = app.block_info().height;
+
+    assert_eq!(query_voting_power_at(&app, &staking, "alice", before_delegation), Uint128::new(500));
+    assert_eq!(query_voting_power_at(&app, &staking, "bob", before_delegation), Uint128::new(200));
+    assert_eq!(query_voting_power_at(&app, &staking, "alice", after_delegation), Uint128::zero());
+    assert_eq!(query_voting_power_at(&app, &staking, "bob", after_delegation), Uint128::new(800));
+
+    // redelegating moves the whole stake to the new delegate
+    delegate(&mut app, &staking, "alice", "carol").unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Unstake {
+            amount: Uint128::new(50),
+        },
+        &[],
+    )
+    .unwrap();
+    app.update_block(|b| b.height += 1);
+    let after_redelegation = app.block_info().height;
+
+    assert_eq!(query_voting_power_at(&app, &staking, "bob", after_redelegation), Uint128::new(200));
+    assert_eq!(query_voting_power_at(&app, &staking, "carol", after_redelegation), Uint128::new(550));
+
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        staking.clone(),
+        &ExecuteMsg::Undelegate {},
+        &[],
+    )
+    .unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking.clone(),
+            &ExecuteMsg::Undelegate {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "votes are not delegated");
+    app.update_block(|b| b.height += 1);
+    let after_undelegation = app.block_info().height;
+
+    assert_eq!(query_voting_power_at(&app, &staking, "alice", after_undelegation), Uint128::new(550));
+    assert_eq!(query_voting_power_at(&app, &staking, "carol", after_undelegation), Uint128::zero());
+    // history is preserved
+    assert_eq!(query_voting_power_at(&app, &staking, "carol", after_redelegation), Uint128::new(550));
+}
+
+fn event_attribute(res: &AppResponse, ty: &str, key: &str) -> String {
+    res.events
+        .iter()
+        .find(|e| e.ty == ty)
+        .unwrap()
+        .attributes
+        .iter()
+        .find(|a| a.key == key)
+        .unwrap()
+        .value
+        .clone()
+}
+
+#[test]
+fn executes_emit_staking_events() {
+    let (mut app, staking) = setup(&[("alice", 1_000), ("bob", 1_000)]);
+    stake(&mut app, &staking, "bob", 250);
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking.clone(),
+            &ExecuteMsg::Stake {},
+            &coins(1_000, DENOM),
+        )
+        .unwrap();
+    assert_eq!(event_attribute(&res, "wasm", ATTR_ACTION), ACTION_STAKE);
+    let ty = format!("wasm-{ACTION_STAKE}");
+    assert_eq!(event_attribute(&res, &ty, ATTR_SENDER), "alice");
+    assert_eq!(event_attribute(&res, &ty, ATTR_AMOUNT), "1000");
+    assert_eq!(event_attribute(&res, &ty, ATTR_NEW_STAKE), "1000");
+    assert_eq!(event_attribute(&res, &ty, ATTR_TOTAL_STAKED), "1250");
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking.clone(),
+            &ExecuteMsg::Unstake {
+                amount: Uint128::new(400),
+            },
+            &[],
+        )
+        .unwrap();
+    let ty = format!("wasm-{ACTION_UNSTAKE}");
+    assert_eq!(event_attribute(&res, &ty, ATTR_AMOUNT), "400");
+    assert_eq!(event_attribute(&res, &ty, ATTR_NEW_STAKE), "600");
+    assert_eq!(event_attribute(&res, &ty, ATTR_TOTAL_STAKED), "850");
+    assert_eq!(
+        event_attribute(&res, &ty, ATTR_RELEASE_TIME),
+        app.block_info().time.plus_seconds(LOCK_PERIOD).seconds().to_string()
+    );
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            staking.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap();
+    let ty = format!("wasm-{ACTION_WITHDRAW}");
+    assert_eq!(event_attribute(&res, &ty, ATTR_AMOUNT), "0");
+}
+
+#[test]
+fn only_listed_reward_denoms_are_distributed() {
+    let (mut app, staking) = setup_with_config(
+        &[("alice", 1_000)],
+        Config {
+            reward_denoms: vec![DENOM.to_string()],
+            ..config(StakingToken::Native {
+                denom: DENOM.to_string(),
+            })
+        },
+    );
+    stake(&mut app, &staking, "alice", 1_000);
+
+    let err = distribute(&mut app, &staking, coins(100, REWARD_DENOM)).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "denom is not accepted as reward");
+
+    let update = |reward_denoms: Vec<String>| {
+        ExecuteMsg::UpdateConfig(UpdateConfig {
+            lock_period: None,
+            max_claims: None,
+            early_exit_penalty: None,
+            penalty_destination: None,
+            reward_denoms: Some(reward_denoms),
+        })
+    };
+
+    let too_many = (0..11).map(|i| format!("udenom{i}")).collect();
+    let err = app
+        .execute_contract(Addr::unchecked(OWNER), staking.clone(), &update(too_many), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: At most 10 reward denoms can be accepted"
+    );
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        staking.clone(),
+        &update(vec![DENOM.to_string(), REWARD_DENOM.to_string()]),
+        &[],
+    )
+    .unwrap();
+    distribute(&mut app, &staking, coins(100, REWARD_DENOM)).unwrap();
+    assert_eq!(
+        query_pending_rewards(&app, &staking, "alice"),
+        coins(100, REWARD_DENOM)
+    );
+}
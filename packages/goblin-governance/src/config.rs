@@ -1,20 +1,62 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
 use cosmwasm_std::{StdError, Decimal, StdResult, Addr, Uint128};
 use crate::constants::proposal_constants::*;
 use cosmwasm_schema::cw_serde;
+use cw_utils::Duration;
+
+/// Where the deposit of a proposal that failed quorum or was vetoed ends up.
+#[cw_serde]
+pub enum DepositDestination {
+    /// Returned to the depositors
+    Refund,
+    Burn,
+    /// Sent to a treasury address
+    Treasury { address: Addr },
+}
+
+impl Display for DepositDestination {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            DepositDestination::Refund => fmt.write_str("refunded"),
+            DepositDestination::Burn => fmt.write_str("burned"),
+            DepositDestination::Treasury { .. } => fmt.write_str("sent_to_treasury"),
+        }
+    }
+}
 
 #[cw_serde]
 pub struct Config {
     pub staking_addr: Addr,
     pub vesting_addr: Addr,
     pub gov_token_denom: String,
-    pub proposal_voting_period: u64,
-    pub proposal_effective_delay: u64,
-    pub proposal_expiration_period: u64,
+    pub proposal_voting_period: Duration,
+    pub proposal_effective_delay: Duration,
+    pub proposal_expiration_period: Duration,
     pub proposal_required_deposit: Uint128,
+    /// Time or number of blocks a proposal has to collect the required deposit
+    pub proposal_deposit_period: Duration,
+    /// Share of the required deposit that has to be paid on submission
+    pub proposal_min_initial_deposit: Decimal,
     pub proposal_required_quorum: Decimal,
     pub proposal_required_threshold: Decimal,
+    pub proposal_veto_threshold: Decimal,
+    /// Voting period of expedited proposals, shorter than the regular one
+    pub expedited_voting_period: Duration,
+    pub expedited_required_deposit: Uint128,
+    pub expedited_required_quorum: Decimal,
+    pub expedited_required_threshold: Decimal,
+    /// Deposit handling of proposals rejected for missing the quorum
+    pub no_quorum_deposit_destination: DepositDestination,
+    /// Deposit handling of vetoed proposals
+    pub veto_deposit_destination: DepositDestination,
+    /// May veto passed proposals during the effective delay
+    pub guardian: Option<Addr>,
+    /// Paid from the keeper pool for every proposal a crank executes, expires
+    /// or ends without refunding its deposit
+    pub keeper_reward: Uint128,
 }
 
 impl Config {
@@ -29,6 +71,16 @@ impl Config {
             )));
         }
 
+        if self.proposal_veto_threshold
+            > Decimal::percent(MAX_PROPOSAL_VETO_THRESHOLD_PERCENTAGE)
+            || self.proposal_veto_threshold
+                < Decimal::percent(MINIMUM_PROPOSAL_VETO_THRESHOLD_PERCENTAGE)
+        {
+            return Err(StdError::generic_err(format!(
+                "The veto threshold for a proposal cannot be lower than {MINIMUM_PROPOSAL_VETO_THRESHOLD_PERCENTAGE}% or higher than {MAX_PROPOSAL_VETO_THRESHOLD_PERCENTAGE}%"
+            )));
+        }
+
         let max_quorum = Decimal::from_str(MAX_PROPOSAL_REQUIRED_QUORUM_PERCENTAGE)?;
         let min_quorum = Decimal::from_str(MINIMUM_PROPOSAL_REQUIRED_QUORUM_PERCENTAGE)?;
         if self.proposal_required_quorum > max_quorum || self.proposal_required_quorum < min_quorum
@@ -40,48 +92,213 @@ impl Config {
             )));
         }
 
-        if !DELAY_INTERVAL.contains(&self.proposal_effective_delay) {
+        validate_period(
+            "effective delay",
+            &self.proposal_effective_delay,
+            &DELAY_INTERVAL,
+            &DELAY_TIME_INTERVAL,
+        )?;
+        validate_period(
+            "expiration period",
+            &self.proposal_expiration_period,
+            &EXPIRATION_PERIOD_INTERVAL,
+            &EXPIRATION_PERIOD_TIME_INTERVAL,
+        )?;
+        validate_period(
+            "voting period",
+            &self.proposal_voting_period,
+            &VOTING_PERIOD_INTERVAL,
+            &VOTING_PERIOD_TIME_INTERVAL,
+        )?;
+
+        if !DEPOSIT_INTERVAL.contains(&self.proposal_required_deposit.u128()) {
             return Err(StdError::generic_err(format!(
-                "The effective delay for a proposal cannot be lower than {} or higher than {}",
-                DELAY_INTERVAL.start(),
-                DELAY_INTERVAL.end()
+                "The required deposit for a proposal cannot be lower than {} or higher than {}",
+                DEPOSIT_INTERVAL.start(),
+                DEPOSIT_INTERVAL.end()
             )));
         }
 
-        if !EXPIRATION_PERIOD_INTERVAL.contains(&self.proposal_expiration_period) {
+        if self.proposal_min_initial_deposit
+            > Decimal::percent(MAX_INITIAL_DEPOSIT_PERCENTAGE)
+            || self.proposal_min_initial_deposit
+                < Decimal::percent(MINIMUM_INITIAL_DEPOSIT_PERCENTAGE)
+        {
+            return Err(StdError::generic_err(format!(
+                "The minimum initial deposit for a proposal cannot be lower than {MINIMUM_INITIAL_DEPOSIT_PERCENTAGE}% or higher than {MAX_INITIAL_DEPOSIT_PERCENTAGE}% of the required deposit"
+            )));
+        }
+
+        validate_period(
+            "deposit period",
+            &self.proposal_deposit_period,
+            &DEPOSIT_PERIOD_INTERVAL,
+            &DEPOSIT_PERIOD_TIME_INTERVAL,
+        )?;
+
+        // Deadlines are chained from one period to the next,
+        // which only works if they are all counted the same way
+        let periods = [
+            self.proposal_voting_period,
+            self.proposal_effective_delay,
+            self.proposal_expiration_period,
+            self.proposal_deposit_period,
+            self.expedited_voting_period,
+        ];
+        if periods
+            .iter()
+            .any(|period| !same_unit(period, &self.proposal_voting_period))
+        {
+            return Err(StdError::generic_err(
+                "All proposal periods have to be either block heights or times",
+            ));
+        }
+
+        self.validate_expedited()
+    }
+
+    fn validate_expedited(&self) -> StdResult<()> {
+        validate_period(
+            "expedited voting period",
+            &self.expedited_voting_period,
+            &EXPEDITED_VOTING_PERIOD_INTERVAL,
+            &EXPEDITED_VOTING_PERIOD_TIME_INTERVAL,
+        )?;
+        if period_value(&self.expedited_voting_period) >= period_value(&self.proposal_voting_period) {
+            return Err(StdError::generic_err(
+                "The voting period for an expedited proposal has to be shorter than the regular one",
+            ));
+        }
+
+        if !EXPEDITED_DEPOSIT_INTERVAL.contains(&self.expedited_required_deposit.u128()) {
             return Err(StdError::generic_err(format!(
-                "The expiration period for a proposal cannot be lower than {} or higher than {}",
-                EXPIRATION_PERIOD_INTERVAL.start(),
-                EXPIRATION_PERIOD_INTERVAL.end()
+                "The required deposit for an expedited proposal cannot be lower than {} or higher than {}",
+                EXPEDITED_DEPOSIT_INTERVAL.start(),
+                EXPEDITED_DEPOSIT_INTERVAL.end()
             )));
         }
+        if self.expedited_required_deposit < self.proposal_required_deposit {
+            return Err(StdError::generic_err(
+                "The required deposit for an expedited proposal cannot be lower than the regular one",
+            ));
+        }
 
-        if !VOTING_PERIOD_INTERVAL.contains(&self.proposal_voting_period) {
+        let max_quorum = Decimal::from_str(MAX_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE)?;
+        let min_quorum = Decimal::from_str(MINIMUM_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE)?;
+        if self.expedited_required_quorum > max_quorum || self.expedited_required_quorum < min_quorum
+        {
             return Err(StdError::generic_err(format!(
-                "The voting period for a proposal should be more than {} or less than {} blocks.",
-                VOTING_PERIOD_INTERVAL.start(),
-                VOTING_PERIOD_INTERVAL.end()
+                "The required quorum for an expedited proposal cannot be lower than {}% or higher than {}%",
+                min_quorum * Decimal::from_ratio(100u8, 1u8),
+                max_quorum * Decimal::from_ratio(100u8, 1u8)
             )));
         }
+        if self.expedited_required_quorum < self.proposal_required_quorum {
+            return Err(StdError::generic_err(
+                "The required quorum for an expedited proposal cannot be lower than the regular one",
+            ));
+        }
 
-        if !DEPOSIT_INTERVAL.contains(&self.proposal_required_deposit.u128()) {
+        if self.expedited_required_threshold
+            > Decimal::percent(MAX_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE)
+            || self.expedited_required_threshold
+                < Decimal::percent(MINIMUM_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE)
+        {
             return Err(StdError::generic_err(format!(
-                "The required deposit for a proposal cannot be lower than {} or higher than {}",
-                DEPOSIT_INTERVAL.start(),
-                DEPOSIT_INTERVAL.end()
+                "The required threshold for an expedited proposal cannot be lower than {MINIMUM_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE}% or higher than {MAX_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE}%"
             )));
         }
+        if self.expedited_required_threshold < self.proposal_required_threshold {
+            return Err(StdError::generic_err(
+                "The required threshold for an expedited proposal cannot be lower than the regular one",
+            ));
+        }
 
         Ok(())
     }
+
+    /// Returns the voting period, deposit, quorum and threshold of a proposal.
+    pub fn proposal_params(&self, expedited: bool) -> ProposalParams {
+        if expedited {
+            ProposalParams {
+                voting_period: self.expedited_voting_period,
+                required_deposit: self.expedited_required_deposit,
+                required_quorum: self.expedited_required_quorum,
+                required_threshold: self.expedited_required_threshold,
+            }
+        } else {
+            ProposalParams {
+                voting_period: self.proposal_voting_period,
+                required_deposit: self.proposal_required_deposit,
+                required_quorum: self.proposal_required_quorum,
+                required_threshold: self.proposal_required_threshold,
+            }
+        }
+    }
+}
+
+/// Checks a period against the range of its unit.
+fn validate_period(
+    name: &str,
+    period: &Duration,
+    blocks: &RangeInclusive<u64>,
+    seconds: &RangeInclusive<u64>,
+) -> StdResult<()> {
+    let (range, unit) = match period {
+        Duration::Height(_) => (blocks, "blocks"),
+        Duration::Time(_) => (seconds, "seconds"),
+    };
+    if !range.contains(&period_value(period)) {
+        return Err(StdError::generic_err(format!(
+            "The {name} for a proposal cannot be lower than {} or higher than {} {unit}",
+            range.start(),
+            range.end()
+        )));
+    }
+    Ok(())
+}
+
+fn period_value(period: &Duration) -> u64 {
+    match period {
+        Duration::Height(height) => *height,
+        Duration::Time(seconds) => *seconds,
+    }
+}
+
+fn same_unit(a: &Duration, b: &Duration) -> bool {
+    matches!(
+        (a, b),
+        (Duration::Height(_), Duration::Height(_)) | (Duration::Time(_), Duration::Time(_))
+    )
+}
+
+/// The parameters that differ between regular and expedited proposals.
+pub struct ProposalParams {
+    pub voting_period: Duration,
+    pub required_deposit: Uint128,
+    pub required_quorum: Decimal,
+    pub required_threshold: Decimal,
 }
 
 #[cw_serde]
 pub struct UpdateConfig {
-    pub proposal_voting_period: Option<u64>,
-    pub proposal_effective_delay: Option<u64>,
-    pub proposal_expiration_period: Option<u64>,
+    pub proposal_voting_period: Option<Duration>,
+    pub proposal_effective_delay: Option<Duration>,
+    pub proposal_expiration_period: Option<Duration>,
     pub proposal_required_deposit: Option<u128>,
+    pub proposal_deposit_period: Option<Duration>,
+    pub proposal_min_initial_deposit: Option<String>,
     pub proposal_required_quorum: Option<String>,
     pub proposal_required_threshold: Option<String>,
+    pub proposal_veto_threshold: Option<String>,
+    pub expedited_voting_period: Option<Duration>,
+    pub expedited_required_deposit: Option<u128>,
+    pub expedited_required_quorum: Option<String>,
+    pub expedited_required_threshold: Option<String>,
+    pub no_quorum_deposit_destination: Option<DepositDestination>,
+    pub veto_deposit_destination: Option<DepositDestination>,
+    pub guardian: Option<String>,
+    /// Removes the guardian, takes precedence over `guardian`
+    pub remove_guardian: Option<bool>,
+    pub keeper_reward: Option<u128>,
 }
\ No newline at end of file
@@ -4,6 +4,8 @@ pub mod proposal_constants {
 
     pub const MINIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 33;
     pub const MAX_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 100;
+    pub const MINIMUM_PROPOSAL_VETO_THRESHOLD_PERCENTAGE: u64 = 10;
+    pub const MAX_PROPOSAL_VETO_THRESHOLD_PERCENTAGE: u64 = 50;
     pub const MAX_PROPOSAL_REQUIRED_QUORUM_PERCENTAGE: &str = "1";
     pub const MINIMUM_PROPOSAL_REQUIRED_QUORUM_PERCENTAGE: &str = "0.01";
     pub const VOTING_PERIOD_INTERVAL: RangeInclusive<u64> = 12342..=7 * 12342;
@@ -12,6 +14,25 @@ pub mod proposal_constants {
     pub const EXPIRATION_PERIOD_INTERVAL: RangeInclusive<u64> = 12342..=100_800;
     // from 10k to 60k $xASTRO
     pub const DEPOSIT_INTERVAL: RangeInclusive<u128> = 10000000000..=60000000000;
+    // from 1 to 7 days in blocks (7 seconds per block)
+    pub const DEPOSIT_PERIOD_INTERVAL: RangeInclusive<u64> = 12342..=7 * 12342;
+    // share of the required deposit that has to be paid on submission
+    pub const MINIMUM_INITIAL_DEPOSIT_PERCENTAGE: u64 = 10;
+    pub const MAX_INITIAL_DEPOSIT_PERCENTAGE: u64 = 100;
+    // from ~3 hours to 1 day in blocks (7 seconds per block)
+    pub const EXPEDITED_VOTING_PERIOD_INTERVAL: RangeInclusive<u64> = 1714..=12342;
+    // from 20k to 120k $xASTRO
+    pub const EXPEDITED_DEPOSIT_INTERVAL: RangeInclusive<u128> = 20000000000..=120000000000;
+    pub const MINIMUM_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 50;
+    pub const MAX_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 100;
+    pub const MAX_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE: &str = "1";
+    pub const MINIMUM_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE: &str = "0.05";
+    // the same periods for time based durations, in seconds
+    pub const VOTING_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 86400..=7 * 86400;
+    pub const DELAY_TIME_INTERVAL: RangeInclusive<u64> = 43200..=100_800;
+    pub const EXPIRATION_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 86400..=705_600;
+    pub const DEPOSIT_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 86400..=7 * 86400;
+    pub const EXPEDITED_VOTING_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 12000..=86400;
     /// Proposal validation attributes
     pub const MIN_TITLE_LENGTH: usize = 4;
     pub const MAX_TITLE_LENGTH: usize = 64;
@@ -24,11 +45,13 @@ pub mod proposal_constants {
 }
 
 #[cfg(feature = "testnet")]
-mod proposal_constants {
+pub mod proposal_constants {
     use std::ops::RangeInclusive;
 
     pub const MINIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 33;
     pub const MAX_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 100;
+    pub const MINIMUM_PROPOSAL_VETO_THRESHOLD_PERCENTAGE: u64 = 10;
+    pub const MAX_PROPOSAL_VETO_THRESHOLD_PERCENTAGE: u64 = 50;
     pub const MAX_PROPOSAL_REQUIRED_QUORUM_PERCENTAGE: &str = "1";
     pub const MINIMUM_PROPOSAL_REQUIRED_QUORUM_PERCENTAGE: &str = "0.001";
     pub const VOTING_PERIOD_INTERVAL: RangeInclusive<u64> = 200..=7 * 12342;
@@ -37,4 +60,32 @@ mod proposal_constants {
     pub const EXPIRATION_PERIOD_INTERVAL: RangeInclusive<u64> = 400..=100_800;
     // from 0.001 to 60k $xASTRO
     pub const DEPOSIT_INTERVAL: RangeInclusive<u128> = 1000..=60000000000;
+    // from ~1400 sec to 7 days in blocks (7 seconds per block)
+    pub const DEPOSIT_PERIOD_INTERVAL: RangeInclusive<u64> = 200..=7 * 12342;
+    // share of the required deposit that has to be paid on submission
+    pub const MINIMUM_INITIAL_DEPOSIT_PERCENTAGE: u64 = 1;
+    pub const MAX_INITIAL_DEPOSIT_PERCENTAGE: u64 = 100;
+    // from ~350 sec to 1 day in blocks (7 seconds per block)
+    pub const EXPEDITED_VOTING_PERIOD_INTERVAL: RangeInclusive<u64> = 50..=12342;
+    // from 0.001 to 120k $xASTRO
+    pub const EXPEDITED_DEPOSIT_INTERVAL: RangeInclusive<u128> = 1000..=120000000000;
+    pub const MINIMUM_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 50;
+    pub const MAX_EXPEDITED_REQUIRED_THRESHOLD_PERCENTAGE: u64 = 100;
+    pub const MAX_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE: &str = "1";
+    pub const MINIMUM_EXPEDITED_REQUIRED_QUORUM_PERCENTAGE: &str = "0.001";
+    // the same periods for time based durations, in seconds
+    pub const VOTING_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 1400..=7 * 86400;
+    pub const DELAY_TIME_INTERVAL: RangeInclusive<u64> = 350..=100_800;
+    pub const EXPIRATION_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 2800..=705_600;
+    pub const DEPOSIT_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 1400..=7 * 86400;
+    pub const EXPEDITED_VOTING_PERIOD_TIME_INTERVAL: RangeInclusive<u64> = 350..=86400;
+    /// Proposal validation attributes
+    pub const MIN_TITLE_LENGTH: usize = 4;
+    pub const MAX_TITLE_LENGTH: usize = 64;
+    pub const MIN_DESC_LENGTH: usize = 4;
+    pub const MAX_DESC_LENGTH: usize = 1024;
+    pub const MIN_LINK_LENGTH: usize = 12;
+    pub const MAX_LINK_LENGTH: usize = 128;
+    /// Special characters that are allowed in proposal text
+    pub const SAFE_TEXT_CHARS: &str = "!&?#()*+'-./\"";
 }
\ No newline at end of file
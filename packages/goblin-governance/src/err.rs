@@ -23,6 +23,9 @@ pub enum ContractError {
     #[error("You don't have any voting power!")]
     NoVotingPower {},
 
+    #[error("Vote weights must be positive, unique per option and sum up to one!")]
+    InvalidVoteWeights {},
+
     #[error("Voting period not ended yet!")]
     VotingPeriodNotEnded {},
 
@@ -32,15 +35,33 @@ pub enum ContractError {
     #[error("Insufficient token deposit!")]
     InsufficientDeposit {},
 
+    #[error("Proposal not in deposit period!")]
+    ProposalNotInDepositPeriod {},
+
+    #[error("Deposit period ended!")]
+    DepositPeriodEnded {},
+
+    #[error("Deposit period not ended yet!")]
+    DepositPeriodNotEnded {},
+
+    #[error("Proposal spends tokens held for deposits or keeper rewards!")]
+    EscrowedFundsSpent {},
+
     #[error("Proposal not passed!")]
     ProposalNotPassed {},
 
     #[error("Proposal not completed!")]
     ProposalNotCompleted {},
 
+    #[error("Proposal already has votes!")]
+    ProposalHasVotes {},
+
     #[error("Proposal delay not ended!")]
     ProposalDelayNotEnded {},
 
+    #[error("Proposal delay ended!")]
+    ProposalDelayEnded {},
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
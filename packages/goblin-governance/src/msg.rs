@@ -1,33 +1,58 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Uint128, CosmosMsg};
+use cosmwasm_std::{Uint128, CosmosMsg, Decimal};
+use cw_utils::Duration;
 
-use crate::{proposal::ProposalVoteOption, config::UpdateConfig};
+use crate::{proposal::{ProposalKind, ProposalVoteOption}, config::{DepositDestination, UpdateConfig}};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub staking_addr: String,
     pub vesting_addr: String,
     pub gov_token_denom: String,
-    pub proposal_voting_period: u64,
-    pub proposal_effective_delay: u64,
-    pub proposal_expiration_period: u64,
+    pub proposal_voting_period: Duration,
+    pub proposal_effective_delay: Duration,
+    pub proposal_expiration_period: Duration,
     pub proposal_required_deposit: Uint128,
+    pub proposal_deposit_period: Duration,
+    pub proposal_min_initial_deposit: String,
     pub proposal_required_quorum: String,
     pub proposal_required_threshold: String,
+    pub proposal_veto_threshold: String,
+    pub expedited_voting_period: Duration,
+    pub expedited_required_deposit: Uint128,
+    pub expedited_required_quorum: String,
+    pub expedited_required_threshold: String,
+    pub no_quorum_deposit_destination: DepositDestination,
+    pub veto_deposit_destination: DepositDestination,
+    pub guardian: Option<String>,
+    pub keeper_reward: Uint128,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     SubmitProposal {
         title: String,
         description: String,
         link: Option<String>,
-        messages: Option<Vec<CosmosMsg>>,
+        kind: ProposalKind,
+        /// Uses the shorter voting period and the stricter deposit,
+        /// quorum and threshold of expedited proposals
+        #[serde(default)]
+        expedited: bool,
     },
     CastVote {
         proposal_id: u64,
         vote: ProposalVoteOption,
     },
+    /// Splits the voting power across several options,
+    /// the weights have to sum up to one
+    CastWeightedVote {
+        proposal_id: u64,
+        options: Vec<(ProposalVoteOption, Decimal)>,
+    },
     EndProposal {
         proposal_id: u64,
     },
@@ -41,5 +66,27 @@ pub enum ExecuteMsg {
     RemoveCompletedProposal {
         proposal_id: u64,
     },
+    /// Withdraws a proposal and refunds its deposit,
+    /// only the submitter can cancel and only before the first vote
+    CancelProposal {
+        proposal_id: u64,
+    },
+    /// Stops a passed proposal during its effective delay,
+    /// only the guardian can veto
+    VetoProposal {
+        proposal_id: u64,
+    },
+    /// Adds the attached funds to the deposit of a proposal,
+    /// voting starts once the required deposit is reached
+    AddDeposit {
+        proposal_id: u64,
+    },
+    /// Ends, executes or expires up to `limit` proposals that are due
+    /// and rewards the caller from the keeper pool
+    Crank {
+        limit: Option<u32>,
+    },
+    /// Adds the attached funds to the keeper pool
+    FundKeeperPool {},
     UpdateConfig(Box<UpdateConfig>),
 }
\ No newline at end of file
@@ -1,7 +1,14 @@
 use std::fmt::{Display, Formatter, Result};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Uint64, Addr, Uint128, CosmosMsg, StdResult, StdError};
+use cosmwasm_std::{
+    to_binary, Uint64, Addr, Uint128, BankMsg, Binary, Coin, CosmosMsg, Decimal, StdResult,
+    StdError, WasmMsg,
+};
+use cw_utils::Expiration;
+use goblin_staking::{config::UpdateConfig as StakingUpdateConfig, msg::ExecuteMsg as StakingExecuteMsg};
+use crate::config::{DepositDestination, UpdateConfig};
+use crate::msg::ExecuteMsg;
 use crate::constants::proposal_constants::*;
 
 use self::helpers::is_safe_link;
@@ -12,23 +19,66 @@ pub struct Proposal {
     pub proposal_id: Uint64,
     pub submitter: Addr,
     pub status: ProposalStatus,
+    /// Expedited proposals that fail fall back to a regular proposal
+    pub expedited: bool,
     pub yes_power: Uint128,
     pub no_power: Uint128,
-    pub yes_voters: Vec<Addr>,
-    pub no_voters: Vec<Addr>,
+    pub abstain_power: Uint128,
+    pub veto_power: Uint128,
+    /// Deposits can be added until the deposit period expires
+    pub deposit_end: Expiration,
+    /// The voting fields below are set once the required deposit is reached
     pub start_block: u64,
     pub start_time: u64,
-    pub end_block: u64,
-    pub delayed_end_block: u64,
-    pub expiration_block: u64,
+    /// Height of the staking snapshot the voting power is taken from
+    pub snapshot_height: u64,
+    /// Total voting power at the snapshot height, used as the quorum denominator
+    pub total_voting_power: Uint128,
+    /// Votes are accepted until the voting period expires
+    pub voting_end: Expiration,
+    /// The proposal can be executed once the effective delay expires
+    pub delay_end: Expiration,
+    /// The proposal can no longer be executed once it expires
+    pub expiration: Expiration,
     pub title: String,
     pub description: String,
     pub link: Option<String>,
-    pub messages: Option<Vec<CosmosMsg>>,
+    /// What the proposal does once executed
+    pub kind: ProposalKind,
+    /// Total amount deposited by all depositors
     pub deposit_amount: Uint128,
+    /// Where the deposit went once the proposal ended, if it has
+    pub deposit_destination: Option<DepositDestination>,
 }
 
 impl Proposal {
+    fn power_mut(&mut self, option: &ProposalVoteOption) -> &mut Uint128 {
+        match option {
+            ProposalVoteOption::For => &mut self.yes_power,
+            ProposalVoteOption::Against => &mut self.no_power,
+            ProposalVoteOption::Abstain => &mut self.abstain_power,
+            ProposalVoteOption::NoWithVeto => &mut self.veto_power,
+        }
+    }
+
+    /// Adds a vote to the tallies of its options.
+    pub fn add_vote(&mut self, vote: &ProposalVote) -> StdResult<()> {
+        for (option, split) in vote.split_power()? {
+            let power = self.power_mut(&option);
+            *power = power.checked_add(split)?;
+        }
+        Ok(())
+    }
+
+    /// Takes a previously added vote back from the tallies of its options.
+    pub fn remove_vote(&mut self, vote: &ProposalVote) -> StdResult<()> {
+        for (option, split) in vote.split_power()? {
+            let power = self.power_mut(&option);
+            *power = power.checked_sub(split)?;
+        }
+        Ok(())
+    }
+
     pub fn validate(&self) -> StdResult<()> {
         // Title validation
         if self.title.len() < MIN_TITLE_LENGTH {
@@ -79,9 +129,82 @@ impl Proposal {
     }
 }
 
+/// This enum describes what a proposal does once executed.
+#[cw_serde]
+pub enum ProposalKind {
+    /// Signalling proposal without messages
+    Text,
+    /// Updates the parameters of the governance contract
+    UpdateGovConfig(Box<UpdateConfig>),
+    /// Updates the parameters of the staking contract
+    UpdateStakingConfig(Box<StakingUpdateConfig>),
+    /// Migrates a contract administered by the governance contract
+    MigrateContract {
+        contract_addr: String,
+        new_code_id: u64,
+        msg: Binary,
+    },
+    /// Sends funds held by the governance contract
+    TreasurySpend { recipient: String, amount: Vec<Coin> },
+    /// Executes arbitrary messages
+    Custom { messages: Vec<CosmosMsg> },
+}
+
+impl ProposalKind {
+    /// Checks the parts of the proposal kind that don't depend on the chain state.
+    pub fn validate(&self) -> StdResult<()> {
+        match self {
+            ProposalKind::TreasurySpend { amount, .. }
+                if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) =>
+            {
+                Err(StdError::generic_err(
+                    "Treasury spend needs a non-zero amount of every coin!",
+                ))
+            }
+            ProposalKind::Custom { messages } if messages.is_empty() => Err(
+                StdError::generic_err("Custom proposal needs at least one message!"),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Compiles the proposal kind into the messages executed by the governance contract.
+    pub fn to_messages(&self, governance: &Addr, staking: &Addr) -> StdResult<Vec<CosmosMsg>> {
+        let messages = match self {
+            ProposalKind::Text => vec![],
+            ProposalKind::UpdateGovConfig(update) => vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: governance.to_string(),
+                msg: to_binary(&ExecuteMsg::UpdateConfig(update.clone()))?,
+                funds: vec![],
+            })],
+            ProposalKind::UpdateStakingConfig(update) => vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: staking.to_string(),
+                msg: to_binary(&StakingExecuteMsg::UpdateConfig(*update.clone()))?,
+                funds: vec![],
+            })],
+            ProposalKind::MigrateContract {
+                contract_addr,
+                new_code_id,
+                msg,
+            } => vec![CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: contract_addr.clone(),
+                new_code_id: *new_code_id,
+                msg: msg.clone(),
+            })],
+            ProposalKind::TreasurySpend { recipient, amount } => vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.clone(),
+                amount: amount.clone(),
+            })],
+            ProposalKind::Custom { messages } => messages.clone(),
+        };
+        Ok(messages)
+    }
+}
+
 /// This enum describes available statuses/states for a Proposal.
 #[cw_serde]
 pub enum ProposalStatus {
+    Deposit,
     Active,
     Passed,
     Rejected,
@@ -89,18 +212,24 @@ pub enum ProposalStatus {
     Failed,
     Executed,
     Expired,
+    Cancelled,
+    /// Stopped by the guardian during the effective delay
+    Vetoed,
 }
 
 impl Display for ProposalStatus {
     fn fmt(&self, fmt: &mut Formatter) -> Result {
         match self {
-            ProposalStatus::Active {} => fmt.write_str("active"),
-            ProposalStatus::Passed {} => fmt.write_str("passed"),
-            ProposalStatus::Rejected {} => fmt.write_str("rejected"),
+            ProposalStatus::Deposit => fmt.write_str("deposit"),
+            ProposalStatus::Active => fmt.write_str("active"),
+            ProposalStatus::Passed => fmt.write_str("passed"),
+            ProposalStatus::Rejected => fmt.write_str("rejected"),
             ProposalStatus::InProgress => fmt.write_str("in_progress"),
             ProposalStatus::Failed => fmt.write_str("failed"),
-            ProposalStatus::Executed {} => fmt.write_str("executed"),
-            ProposalStatus::Expired {} => fmt.write_str("expired"),
+            ProposalStatus::Executed => fmt.write_str("executed"),
+            ProposalStatus::Expired => fmt.write_str("expired"),
+            ProposalStatus::Cancelled => fmt.write_str("cancelled"),
+            ProposalStatus::Vetoed => fmt.write_str("vetoed"),
         }
     }
 }
@@ -108,24 +237,78 @@ impl Display for ProposalStatus {
 /// This structure describes a proposal vote.
 #[cw_serde]
 pub struct ProposalVote {
-    /// Voted option for the proposal
-    pub option: ProposalVoteOption,
+    /// Voted options for the proposal with their weights, summing up to one
+    pub options: Vec<(ProposalVoteOption, Decimal)>,
     /// Vote power
     pub power: Uint128,
 }
 
+impl ProposalVote {
+    /// Creates a vote putting the whole `power` on a single option.
+    pub fn single(option: ProposalVoteOption, power: Uint128) -> Self {
+        ProposalVote {
+            options: vec![(option, Decimal::one())],
+            power,
+        }
+    }
+
+    /// Returns true if any part of the vote went to `option`.
+    pub fn has_option(&self, option: &ProposalVoteOption) -> bool {
+        self.options.iter().any(|(voted, _)| voted == option)
+    }
+
+    /// Splits the vote power across the voted options. The last option
+    /// receives the rounding remainder, so the parts always add up to the power.
+    pub fn split_power(&self) -> StdResult<Vec<(ProposalVoteOption, Uint128)>> {
+        let mut remaining = self.power;
+        let mut parts = Vec::with_capacity(self.options.len());
+        for (i, (option, weight)) in self.options.iter().enumerate() {
+            let part = if i + 1 == self.options.len() {
+                remaining
+            } else {
+                self.power * *weight
+            };
+            remaining = remaining.checked_sub(part)?;
+            parts.push((option.clone(), part));
+        }
+        Ok(parts)
+    }
+}
+
+impl Display for ProposalVote {
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self.options.as_slice() {
+            [(option, _)] => write!(fmt, "{}", option),
+            options => {
+                let options: Vec<String> = options
+                    .iter()
+                    .map(|(option, weight)| format!("{}:{}", option, weight))
+                    .collect();
+                fmt.write_str(&options.join(","))
+            }
+        }
+    }
+}
+
 /// This enum describes available options for voting on a proposal.
 #[cw_serde]
 pub enum ProposalVoteOption {
     For,
     Against,
+    /// Counts towards the quorum but not the threshold
+    Abstain,
+    /// Counts against the proposal and, above the veto threshold,
+    /// rejects it and burns its deposit
+    NoWithVeto,
 }
 
 impl Display for ProposalVoteOption {
     fn fmt(&self, fmt: &mut Formatter) -> Result {
         match self {
-            ProposalVoteOption::For {} => fmt.write_str("for"),
-            ProposalVoteOption::Against {} => fmt.write_str("against"),
+            ProposalVoteOption::For => fmt.write_str("for"),
+            ProposalVoteOption::Against => fmt.write_str("against"),
+            ProposalVoteOption::Abstain => fmt.write_str("abstain"),
+            ProposalVoteOption::NoWithVeto => fmt.write_str("no_with_veto"),
         }
     }
 }
@@ -139,6 +322,10 @@ pub struct ProposalVotesResponse {
     pub for_power: Uint128,
     /// Total amount of `against` votes for a proposal.
     pub against_power: Uint128,
+    /// Total amount of `abstain` votes for a proposal.
+    pub abstain_power: Uint128,
+    /// Total amount of `no_with_veto` votes for a proposal.
+    pub veto_power: Uint128,
 }
 
 /// This structure describes a proposal list response.
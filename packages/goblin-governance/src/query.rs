@@ -1,7 +1,7 @@
 #![allow(unused_imports)]
 use cosmwasm_schema::{QueryResponses, cw_serde};
-use cosmwasm_std::{Uint64, Uint128, Addr};
-use crate::{config::Config, proposal::{ProposalVoteOption, Proposal}};
+use cosmwasm_std::{Uint64, Uint128, Addr, Order};
+use crate::{config::Config, proposal::{ProposalVoteOption, ProposalVote, Proposal, ProposalStatus}};
 
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -12,14 +12,31 @@ pub enum QueryMsg {
     Proposals {
         start: Option<u64>,
         limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    #[returns(ProposalListResponse)]
+    ProposalsByStatus {
+        status: ProposalStatus,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    #[returns(ProposalListResponse)]
+    ProposalsBySubmitter {
+        submitter: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
     },
     #[returns(Vec<Addr>)]
     ProposalVoters {
         proposal_id: u64,
         vote_option: ProposalVoteOption,
-        start: Option<u64>,
+        start_after: Option<String>,
         limit: Option<u32>,
     },
+    #[returns(Option<ProposalVote>)]
+    Vote { proposal_id: u64, voter: String },
     #[returns(Proposal)]
     Proposal { proposal_id: u64 },
     #[returns(ProposalVotesResponse)]
@@ -28,6 +45,26 @@ pub enum QueryMsg {
     UserVotingPower { user: String, proposal_id: u64 },
     #[returns(Uint128)]
     TotalVotingPower { proposal_id: u64 },
+    #[returns(ProposalExecutionResponse)]
+    ExecutionResult { proposal_id: u64 },
+    #[returns(Uint128)]
+    KeeperPool {},
+}
+
+/// Order of a proposal list by proposal id, ascending by default.
+#[cw_serde]
+pub enum OrderBy {
+    Asc,
+    Desc,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Order {
+        match order {
+            OrderBy::Asc => Order::Ascending,
+            OrderBy::Desc => Order::Descending,
+        }
+    }
 }
 
 /// This structure describes a proposal vote response.
@@ -39,6 +76,21 @@ pub struct ProposalVotesResponse {
     pub for_power: Uint128,
     /// Total amount of `against` votes for a proposal.
     pub against_power: Uint128,
+    /// Total amount of `abstain` votes for a proposal.
+    pub abstain_power: Uint128,
+    /// Total amount of `no_with_veto` votes for a proposal.
+    pub veto_power: Uint128,
+}
+
+/// This structure describes the execution result of a proposal.
+#[cw_serde]
+pub struct ProposalExecutionResponse {
+    /// Proposal identifier
+    pub proposal_id: u64,
+    /// Current proposal status
+    pub status: ProposalStatus,
+    /// Error returned by the first failed proposal message
+    pub error: Option<String>,
 }
 
 /// This structure describes a proposal list response.
@@ -1,7 +1,7 @@
 use crate::config::Config;
-use crate::proposal::Proposal;
-use cosmwasm_std::{Uint64, Coin, Storage, StdResult, StdError};
-use cw_storage_plus::{Item, Map};
+use crate::proposal::{Proposal, ProposalVote};
+use cosmwasm_std::{Addr, Uint64, Uint128, Coin, Empty, Storage, StdResult, StdError};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 /// Stores the config for the Assembly contract
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -9,8 +9,70 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// Stores the global state for the Assembly contract
 pub const PROPOSAL_COUNT: Item<Uint64> = Item::new("proposal_count");
 
+/// Secondary indexes of the proposals
+pub struct ProposalIndexes<'a> {
+    /// Proposals by their status, keyed by the status name
+    pub status: MultiIndex<'a, String, Proposal, u64>,
+    /// Proposals by the address that submitted them
+    pub submitter: MultiIndex<'a, Addr, Proposal, u64>,
+}
+
+impl<'a> IndexList<Proposal> for ProposalIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Proposal>> + '_> {
+        let v: Vec<&dyn Index<Proposal>> = vec![&self.status, &self.submitter];
+        Box::new(v.into_iter())
+    }
+}
+
 /// This is a map that contains information about all proposals
-pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+pub fn proposals<'a>() -> IndexedMap<'a, u64, Proposal, ProposalIndexes<'a>> {
+    let indexes = ProposalIndexes {
+        status: MultiIndex::new(
+            |_, proposal| proposal.status.to_string(),
+            "proposals",
+            "proposals__status",
+        ),
+        submitter: MultiIndex::new(
+            |_, proposal| proposal.submitter.clone(),
+            "proposals",
+            "proposals__submitter",
+        ),
+    };
+    IndexedMap::new("proposals", indexes)
+}
+
+/// Contains the vote of every voter, keyed by proposal id and voter
+pub const VOTES: Map<(u64, &Addr), ProposalVote> = Map::new("votes");
+
+/// Lists the voters of every vote option, keyed by proposal id, option and voter
+pub const OPTION_VOTERS: Map<(u64, &str, &Addr), Empty> = Map::new("option_voters");
+
+/// Saves the vote of `voter` and lists them under the options of the vote
+/// instead of the options of their previous vote.
+pub fn save_vote(
+    store: &mut dyn Storage,
+    proposal_id: u64,
+    voter: &Addr,
+    vote: &ProposalVote,
+    previous_vote: Option<&ProposalVote>,
+) -> StdResult<()> {
+    for (option, _) in previous_vote.map_or(&[][..], |vote| &vote.options[..]) {
+        OPTION_VOTERS.remove(store, (proposal_id, &option.to_string(), voter));
+    }
+    for (option, _) in &vote.options {
+        OPTION_VOTERS.save(store, (proposal_id, &option.to_string(), voter), &Empty {})?;
+    }
+    VOTES.save(store, (proposal_id, voter), vote)
+}
+
+/// Contains the amount deposited by every depositor, keyed by proposal id and depositor
+pub const DEPOSITS: Map<(u64, &Addr), Uint128> = Map::new("deposits");
+
+/// Funds set aside for the keeper rewards, kept apart from the proposal deposits
+pub const KEEPER_POOL: Item<Uint128> = Item::new("keeper_pool");
+
+/// Contains the error of the first failed message of every failed proposal
+pub const EXECUTION_ERRORS: Map<u64, String> = Map::new("execution_errors");
 
 pub fn get_gov_token_denom( store: &dyn Storage ) -> StdResult<String>{
 
@@ -30,6 +92,6 @@ pub fn validate_funds(store: &dyn Storage, coins: Vec<Coin>) -> StdResult<Coin>
         return Err(StdError::GenericErr { msg: String::from("funds shall only contain valid denom") });
     }
 
-    return Ok(coins[0].clone());
+    Ok(coins[0].clone())
 
 }
\ No newline at end of file
@@ -1,8 +1,202 @@
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Decimal, StdError, StdResult, Uint128, WasmMsg, to_binary};
+use cw20::Cw20ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 
+// the token that is accepted for staking
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum StakingToken {
+    Native { denom: String },
+    Cw20 { contract_addr: Addr },
+}
+
+impl StakingToken {
+
+    // message sending "amount" staking tokens
+    // from the contract to "recipient"
+    pub fn transfer_msg(
+        &self,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+
+        let msg = match self {
+            StakingToken::Native { denom } => BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }],
+            }.into(),
+            StakingToken::Cw20 { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }.into(),
+        };
+        Ok(msg)
+
+    }
+
+    // message burning "amount" staking tokens
+    // held by the contract
+    pub fn burn_msg(
+        &self,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+
+        let msg = match self {
+            StakingToken::Native { denom } => BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }],
+            }.into(),
+            StakingToken::Cw20 { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+                funds: vec![],
+            }.into(),
+        };
+        Ok(msg)
+
+    }
+
+}
+
+// where the penalty of an instant unstake ends up
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum PenaltyDestination {
+    Burn,
+    Address { address: Addr },
+    // paid out to the remaining stakers through the rewards index
+    Stakers,
+}
+
+// every reward denom is accrued on each stake change,
+// so their number has to stay small
+pub const MAX_REWARD_DENOMS: usize = 10;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub denom: String,
+    // the governance contract
+    pub owner: Addr,
+    pub token: StakingToken,
     pub lock_period: u64,
+    // seconds of release time merged into a single claim
+    pub claim_epoch: u64,
+    // maximum number of outstanding claims per address
+    pub max_claims: u32,
+    // fraction of the amount withheld on instant unstakes
+    pub early_exit_penalty: Decimal,
+    pub penalty_destination: PenaltyDestination,
+    // native denoms accepted by DistributeRewards
+    pub reward_denoms: Vec<String>,
+}
+
+impl Config {
+
+    pub fn validate(&self) -> StdResult<()> {
+
+        validate_early_exit_penalty(self.early_exit_penalty)?;
+
+        if self.claim_epoch == 0 {
+            return Err(StdError::generic_err(
+                "The claim epoch cannot be zero"
+            ));
+        }
+
+        validate_max_claims(self.max_claims)?;
+
+        // rewards are paid out as native coins only
+        if let (StakingToken::Cw20 { .. }, PenaltyDestination::Stakers) = (&self.token, &self.penalty_destination) {
+            return Err(StdError::generic_err(
+                "Penalties of cw20 staking tokens cannot be distributed to stakers"
+            ));
+        }
+
+        validate_reward_denoms(&self.reward_denoms)?;
+
+        Ok(())
+
+    }
+
+}
+
+fn validate_early_exit_penalty(early_exit_penalty: Decimal) -> StdResult<()> {
+
+    if early_exit_penalty > Decimal::one() {
+        return Err(StdError::generic_err(
+            "The early exit penalty cannot be higher than 100%"
+        ));
+    }
+    Ok(())
+
+}
+
+fn validate_max_claims(max_claims: u32) -> StdResult<()> {
+
+    if max_claims == 0 {
+        return Err(StdError::generic_err(
+            "At least one claim per address has to be allowed"
+        ));
+    }
+    Ok(())
+
+}
+
+fn validate_reward_denoms(reward_denoms: &[String]) -> StdResult<()> {
+
+    if reward_denoms.len() > MAX_REWARD_DENOMS {
+        return Err(StdError::generic_err(format!(
+            "At most {MAX_REWARD_DENOMS} reward denoms can be accepted"
+        )));
+    }
+
+    let mut denoms = reward_denoms.to_vec();
+    denoms.sort();
+    denoms.dedup();
+    if denoms.len() != reward_denoms.len() {
+        return Err(StdError::generic_err(
+            "Reward denoms cannot be listed twice"
+        ));
+    }
+    Ok(())
+
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UpdateConfig {
+    pub lock_period: Option<u64>,
+    pub max_claims: Option<u32>,
+    pub early_exit_penalty: Option<Decimal>,
+    pub penalty_destination: Option<PenaltyDestination>,
+    pub reward_denoms: Option<Vec<String>>,
+}
+
+impl UpdateConfig {
+
+    // checks the updated values that are valid regardless of the current config,
+    // so that proposals changing them can be rejected before they are voted on
+    pub fn validate(&self) -> StdResult<()> {
+
+        if let Some(early_exit_penalty) = self.early_exit_penalty {
+            validate_early_exit_penalty(early_exit_penalty)?;
+        }
+
+        if let Some(max_claims) = self.max_claims {
+            validate_max_claims(max_claims)?;
+        }
+
+        if let Some(reward_denoms) = &self.reward_denoms {
+            validate_reward_denoms(reward_denoms)?;
+        }
+
+        Ok(())
+
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128};
+use cw_storage_plus::{SnapshotMap, Strategy};
+
+use crate::err::ContractError;
+use crate::state::STAKES;
+
+// the address each delegator has handed its voting power to
+pub const DELEGATIONS: SnapshotMap<Addr, Addr> = SnapshotMap::new(
+    "delegations",
+    "delegations__check",
+    "delegations__change",
+    Strategy::EveryBlock,
+);
+
+// the sum of all stakes delegated to an address
+pub const DELEGATED_POWER: SnapshotMap<Addr, Uint128> = SnapshotMap::new(
+    "delegated_power",
+    "delegated_power__check",
+    "delegated_power__change",
+    Strategy::EveryBlock,
+);
+
+fn add_delegated_power(
+    store: &mut dyn Storage,
+    delegate: Addr,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+
+    let power = DELEGATED_POWER
+        .may_load(store, delegate.clone())?
+        .unwrap_or_default();
+    DELEGATED_POWER.save(store, delegate, &power.checked_add(amount)?, height)
+
+}
+
+fn sub_delegated_power(
+    store: &mut dyn Storage,
+    delegate: Addr,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+
+    let power = DELEGATED_POWER
+        .may_load(store, delegate.clone())?
+        .unwrap_or_default();
+    DELEGATED_POWER.save(store, delegate, &power.checked_sub(amount)?, height)
+
+}
+
+// move the voting power of a stake change of "address"
+// along to its delegate, if it has one
+pub fn increase_delegated_power(
+    store: &mut dyn Storage,
+    address: Addr,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+
+    match DELEGATIONS.may_load(store, address)? {
+        Some(delegate) => add_delegated_power(store, delegate, amount, height),
+        None => Ok(()),
+    }
+
+}
+
+pub fn decrease_delegated_power(
+    store: &mut dyn Storage,
+    address: Addr,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+
+    match DELEGATIONS.may_load(store, address)? {
+        Some(delegate) => sub_delegated_power(store, delegate, amount, height),
+        None => Ok(()),
+    }
+
+}
+
+// hand the voting power of the stake of "delegator" to "delegate".
+// delegated power is not passed on by the delegate
+pub fn delegate_votes(
+    store: &mut dyn Storage,
+    delegator: Addr,
+    delegate: Addr,
+    height: u64,
+) -> Result<(), ContractError> {
+
+    if delegator == delegate {
+        return Err(ContractError::SelfDelegation{});
+    }
+
+    let stake = STAKES
+        .may_load(store, delegator.clone())?
+        .unwrap_or_default();
+    if let Some(previous) = DELEGATIONS.may_load(store, delegator.clone())? {
+        sub_delegated_power(store, previous, stake, height)?;
+    }
+    add_delegated_power(store, delegate.clone(), stake, height)?;
+    DELEGATIONS.save(store, delegator, &delegate, height)?;
+    Ok(())
+
+}
+
+// take the voting power of "delegator" back from its delegate
+pub fn undelegate_votes(
+    store: &mut dyn Storage,
+    delegator: Addr,
+    height: u64,
+) -> Result<(), ContractError> {
+
+    let delegate = DELEGATIONS
+        .may_load(store, delegator.clone())?
+        .ok_or(ContractError::NotDelegating{})?;
+    let stake = STAKES
+        .may_load(store, delegator.clone())?
+        .unwrap_or_default();
+    sub_delegated_power(store, delegate, stake, height)?;
+    DELEGATIONS.remove(store, delegator, height)?;
+    Ok(())
+
+}
+
+// the voting power of "address" at the beginning of block "height":
+// its own stake unless delegated away, plus all stakes delegated to it
+pub fn get_voting_power_at(
+    store: &dyn Storage,
+    address: Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+
+    let delegated = DELEGATED_POWER
+        .may_load_at_height(store, address.clone(), height)?
+        .unwrap_or_default();
+    let own = match DELEGATIONS.may_load_at_height(store, address.clone(), height)? {
+        Some(_) => Uint128::zero(),
+        None => STAKES
+            .may_load_at_height(store, address, height)?
+            .unwrap_or_default(),
+    };
+    Ok(own.checked_add(delegated)?)
+
+}
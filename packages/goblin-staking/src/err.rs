@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -7,4 +7,26 @@ pub enum ContractError {
     Std(#[from] StdError),
     #[error("insufficient funds")]
     InsufficientFunds{},
+    #[error("denom is not accepted as reward")]
+    InvalidRewardDenom{},
+    #[error("no tokens are staked")]
+    NoStakers{},
+    #[error("token is not accepted for staking")]
+    InvalidToken{},
+    #[error("not enough unbonding tokens")]
+    InsufficientClaims{},
+    #[error("too many outstanding claims, withdraw or rebond first")]
+    TooManyClaims{},
+    #[error("cannot delegate votes to yourself")]
+    SelfDelegation{},
+    #[error("votes are not delegated")]
+    NotDelegating{},
+    #[error("unauthorized")]
+    Unauthorized{},
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
 }
\ No newline at end of file
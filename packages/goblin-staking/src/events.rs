@@ -0,0 +1,33 @@
+// Every staking execute sets `action` on the default wasm event
+// and emits a `wasm-<action>` event with the attributes below.
+
+// actions, doubling as the custom event types
+pub const ACTION_STAKE: &str = "stake";
+pub const ACTION_UNSTAKE: &str = "unstake";
+pub const ACTION_UNSTAKE_INSTANT: &str = "unstake_instant";
+pub const ACTION_REBOND: &str = "rebond";
+pub const ACTION_WITHDRAW: &str = "withdraw";
+pub const ACTION_DISTRIBUTE_REWARDS: &str = "distribute_rewards";
+pub const ACTION_CLAIM_REWARDS: &str = "claim_rewards";
+pub const ACTION_UPDATE_CONFIG: &str = "update_config";
+pub const ACTION_PROPOSE_NEW_OWNER: &str = "propose_new_owner";
+pub const ACTION_ACCEPT_OWNERSHIP: &str = "accept_ownership";
+pub const ACTION_DELEGATE_VOTES: &str = "delegate_votes";
+pub const ACTION_UNDELEGATE: &str = "undelegate";
+
+// set on every event
+pub const ATTR_ACTION: &str = "action";
+pub const ATTR_SENDER: &str = "sender";
+// staking tokens moved by the action, or the
+// comma separated coins for reward actions
+pub const ATTR_AMOUNT: &str = "amount";
+// stake of the sender after the action
+pub const ATTR_NEW_STAKE: &str = "new_stake";
+// total stake of the contract after the action
+pub const ATTR_TOTAL_STAKED: &str = "total_staked";
+// unix seconds the created claim is released at
+pub const ATTR_RELEASE_TIME: &str = "release_time";
+// part of an instant unstake withheld as penalty
+pub const ATTR_PENALTY: &str = "penalty";
+pub const ATTR_OWNER: &str = "owner";
+pub const ATTR_DELEGATE: &str = "delegate";
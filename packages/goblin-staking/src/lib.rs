@@ -3,4 +3,7 @@ pub mod state;
 pub mod query;
 pub mod err;
 pub mod msg;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod rewards;
+pub mod delegation;
+pub mod events;
\ No newline at end of file
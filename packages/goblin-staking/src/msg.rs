@@ -1,8 +1,9 @@
 use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 
-use crate::config::Config;
+use crate::config::{Config, UpdateConfig};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum ExecuteMsg {
@@ -11,6 +12,32 @@ pub enum ExecuteMsg {
         amount: Uint128,
     },
     Withdraw{},
+    UnstakeInstant{
+        amount: Uint128,
+    },
+    Rebond{
+        amount: Uint128,
+    },
+    DistributeRewards{},
+    ClaimRewards{},
+    // cw20 contracts dispatch `{"receive": ...}`
+    #[serde(rename = "receive")]
+    Receive(Cw20ReceiveMsg),
+    UpdateConfig(UpdateConfig),
+    ProposeNewOwner{
+        owner: String,
+    },
+    AcceptOwnership{},
+    DelegateVotes{
+        to: String,
+    },
+    Undelegate{},
+}
+
+// hook messages accepted along with a cw20 `Send`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum Cw20HookMsg {
+    Stake{},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -1,20 +1,43 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
+use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 use crate::claim::Claims;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum QueryMsg {
-    Stakers { },
+    Config {},
+    Stakers {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    },
     Staked { address: Addr },
     StakedAt { address: Addr, height: u64},
     TotalStaked {},
     TotalStakedAt { height: u64 },
-    Claims { address: Addr },
+    Claims {
+        address: Addr,
+        start_after: Option<Timestamp>,
+        limit: Option<u32>,
+    },
+    PendingRewards { address: Addr },
+    VotingPowerAt { address: Addr, height: u64 },
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakerInfo {
+    pub address: Addr,
+    pub staked: Uint128,
+    // only set if the query asked for a height
+    pub staked_at: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct QueryStakersResp {
-    pub stakers: Vec<String>
+    pub stakers: Vec<StakerInfo>,
+    // pass as `start_after` to fetch the next page,
+    // `None` once all stakers have been returned
+    pub next_start_after: Option<Addr>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -24,9 +47,19 @@ pub struct QueryStakedResp {
 
 #[derive(Serialize, Deserialize)]
 pub struct QueryClaimsResp {
+    // released and locked claims of the requested page
     pub released:           Claims,
-    pub total_released:     Uint128,
     pub locked:             Claims,
+    // totals over all claims of the address
+    pub total_released:     Uint128,
     pub total_locked:       Uint128,
     pub total:              Uint128,
+    // release of the last claim of the page,
+    // `None` once all claims have been returned
+    pub next_start_after:   Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryPendingRewardsResp {
+    pub rewards: Vec<Coin>,
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+use cosmwasm_std::{Addr, Coin, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{STAKES, TOTAL_STAKED};
+
+// checkpoint of a single stakers rewards in one denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardCheckpoint {
+    // reward index the pending amount was last accrued at
+    pub index: Decimal,
+    // rewards accrued but not claimed yet
+    pub pending: Uint128,
+}
+
+// global amount of rewards paid per staked token, per reward denom
+pub const REWARD_INDEX: Map<String, Decimal> = Map::new(
+    "reward_index"
+);
+
+pub const USER_REWARDS: Map<(Addr, String), RewardCheckpoint> = Map::new(
+    "user_rewards"
+);
+
+// spread the given funds over all currently staked tokens
+// by raising the reward index of each denom
+pub fn distribute_rewards(
+    store: &mut dyn Storage,
+    funds: Vec<Coin>,
+) -> StdResult<()> {
+
+    let total_staked = TOTAL_STAKED.load(store)?;
+    for coin in funds.into_iter().filter(|c| !c.amount.is_zero()) {
+        let increment = Decimal::from_ratio(coin.amount, total_staked);
+        REWARD_INDEX.update(store, coin.denom, |index| -> StdResult<Decimal> {
+            Ok(index.unwrap_or_default().checked_add(increment)?)
+        })?;
+    }
+    Ok(())
+
+}
+
+fn accrue(
+    checkpoint: &mut RewardCheckpoint,
+    index: Decimal,
+    stake: Uint128,
+) -> StdResult<()> {
+
+    let accrued = stake * index.checked_sub(checkpoint.index)?;
+    checkpoint.pending = checkpoint.pending.checked_add(accrued)?;
+    checkpoint.index = index;
+    Ok(())
+
+}
+
+// accrue the rewards earned by the current stake of "address"
+// up to now. must be called before the stake changes
+pub fn update_rewards(
+    store: &mut dyn Storage,
+    address: Addr,
+) -> StdResult<()> {
+
+    let stake = STAKES
+        .may_load(store, address.clone())?
+        .unwrap_or_default();
+    let indices = REWARD_INDEX
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (denom, index) in indices {
+        let key = (address.clone(), denom);
+        let mut checkpoint = USER_REWARDS
+            .may_load(store, key.clone())?
+            .unwrap_or_default();
+        accrue(&mut checkpoint, index, stake)?;
+        USER_REWARDS.save(store, key, &checkpoint)?;
+    }
+    Ok(())
+
+}
+
+// rewards of "address" that have not been claimed yet
+pub fn get_pending_rewards(
+    store: &dyn Storage,
+    address: Addr,
+) -> StdResult<Vec<Coin>> {
+
+    let stake = STAKES
+        .may_load(store, address.clone())?
+        .unwrap_or_default();
+
+    let indices = REWARD_INDEX
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut rewards = vec![];
+    for (denom, index) in indices {
+        let mut checkpoint = USER_REWARDS
+            .may_load(store, (address.clone(), denom.clone()))?
+            .unwrap_or_default();
+        accrue(&mut checkpoint, index, stake)?;
+        if !checkpoint.pending.is_zero() {
+            rewards.push(Coin {
+                denom,
+                amount: checkpoint.pending,
+            });
+        }
+    }
+    Ok(rewards)
+
+}
+
+// reset the pending rewards of "address" and return
+// the amounts that have to be paid out
+pub fn claim_rewards(
+    store: &mut dyn Storage,
+    address: Addr,
+) -> StdResult<Vec<Coin>> {
+
+    update_rewards(store, address.clone())?;
+
+    let checkpoints = USER_REWARDS
+        .prefix(address.clone())
+        .range(store, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut rewards = vec![];
+    for (denom, mut checkpoint) in checkpoints {
+        if checkpoint.pending.is_zero() {
+            continue;
+        }
+        rewards.push(Coin {
+            denom: denom.clone(),
+            amount: checkpoint.pending,
+        });
+        checkpoint.pending = Uint128::zero();
+        USER_REWARDS.save(store, (address.clone(), denom), &checkpoint)?;
+    }
+    Ok(rewards)
+
+}
@@ -1,6 +1,7 @@
-use cosmwasm_std::{Addr, Uint128, Coin, Storage, StdResult, Timestamp};
-use cw_storage_plus::{SnapshotMap, Strategy, Item, Map, SnapshotItem};
-use crate::{claim::{Claim, Claims}, config::Config};
+use cosmwasm_std::{Addr, Uint128, Coin, Storage, StdResult, Timestamp, Order};
+use cw_storage_plus::{SnapshotMap, Strategy, Item, Map, SnapshotItem, Bound};
+use crate::{claim::{Claim, Claims}, config::{Config, StakingToken}, err::ContractError, rewards::update_rewards};
+use crate::delegation::{increase_delegated_power, decrease_delegated_power};
 
 pub const STAKES: SnapshotMap<Addr, Uint128> = SnapshotMap::new(
     "stakes",
@@ -16,7 +17,9 @@ pub const TOTAL_STAKED: SnapshotItem<Uint128> = SnapshotItem::new(
     Strategy::EveryBlock,
 );
 
-pub const CLAIMS: Map<Addr, Claims> = Map::new(
+// claims keyed by owner and the start of the epoch they release in.
+// all claims of an owner releasing in the same epoch are merged
+pub const CLAIMS: Map<(Addr, u64), Claim> = Map::new(
     "claims"
 );
 
@@ -24,6 +27,11 @@ pub const CONFIG: Item<Config> = Item::new(
     "config",
 );
 
+// owner proposed by the current owner, waiting to accept
+pub const PENDING_OWNER: Item<Addr> = Item::new(
+    "pending_owner",
+);
+
 // input can be a list of coins. output the total
 // amount of staking tokens from the funds input
 pub fn get_staking_amount_from_funds(
@@ -31,26 +39,29 @@ pub fn get_staking_amount_from_funds(
     funds: Vec<Coin>,
 ) -> StdResult<Uint128> {
     
-    let staking_denom = get_staking_denom(store)?;
-    let amount: StdResult<Uint128> = funds
+    // cw20 tokens are never sent along as funds
+    let staking_denom = match get_staking_token(store)? {
+        StakingToken::Native { denom } => denom,
+        StakingToken::Cw20 { .. } => return Ok(Uint128::zero()),
+    };
+    funds
         .into_iter()
-        .fold(Ok(Uint128::from(0u16)), |cum: StdResult<Uint128>, coin: Coin| -> StdResult<Uint128>  {
-            let mut ret = cum?;
+        .try_fold(Uint128::from(0u16), |cum: Uint128, coin: Coin| -> StdResult<Uint128>  {
+            let mut ret = cum;
             if coin.denom == staking_denom {
                 ret = ret.checked_add(coin.amount)?
             }
             Ok(ret)
-        });
-    Ok(amount?)
+        })
        
 }
 
-// get the staking denom from the config
-pub fn get_staking_denom(
+// get the staking token from the config
+pub fn get_staking_token(
     store: &dyn Storage
-) -> StdResult<String> {
+) -> StdResult<StakingToken> {
 
-    Ok(CONFIG.load(store)?.denom)
+    Ok(CONFIG.load(store)?.token)
 
 }
 
@@ -62,15 +73,17 @@ pub fn insert_stake(
     height: u64,
 ) -> StdResult<()> {
 
+    update_rewards(store, address.clone())?;
     let total_stake = TOTAL_STAKED.load(store)?;
     let stake = STAKES
         .load(store, address.clone())
         .unwrap_or(Uint128::new(0u128));
     let new_stake = stake.checked_add(amount)?;
     let new_total_stake = total_stake.checked_add(amount)?;
-    STAKES.save(store, address, &new_stake, height)?;
+    STAKES.save(store, address.clone(), &new_stake, height)?;
     TOTAL_STAKED.save(store, &new_total_stake, height)?;
-    return Ok(())
+    increase_delegated_power(store, address, amount, height)?;
+    Ok(())
 
 }
 
@@ -83,15 +96,17 @@ pub fn remove_stake(
     height: u64,
 ) -> StdResult<()> {
 
+    update_rewards(store, address.clone())?;
     let total_stake = TOTAL_STAKED.load(store)?;
     let stake = STAKES
         .load(store, address.clone())
         .unwrap_or(Uint128::new(0u128));
     let new_amount = stake.checked_sub(amount)?;
     let new_total_stake = total_stake.checked_sub(amount)?;
-    STAKES.save(store, address, &new_amount, height)?;
+    STAKES.save(store, address.clone(), &new_amount, height)?;
     TOTAL_STAKED.save(store, &new_total_stake, height)?;
-    return Ok(())
+    decrease_delegated_power(store, address, amount, height)?;
+    Ok(())
 
 }
 
@@ -108,22 +123,40 @@ pub fn get_total_staked_per_address_at(
 
 }
 
-// add a claim to the list of claims
-// for a particular user
+// add a claim to the claims of a particular user,
+// merging it into the bucket of its release epoch
 pub fn add_claim(
     store: &mut dyn Storage,
     address: Addr,
     amount: Uint128,
     release: Timestamp
-) -> StdResult<()> {
-
-    let mut claims = CLAIMS.load(store, address.clone()).unwrap_or(Claims::new());
-    let new_claim = Claim{
-        amount: amount,
-        release: release,
+) -> Result<(), ContractError> {
+
+    let config = CONFIG.load(store)?;
+    let bucket = release.seconds() / config.claim_epoch * config.claim_epoch;
+    let key = (address.clone(), bucket);
+
+    let claim = match CLAIMS.may_load(store, key.clone())? {
+        Some(claim) => Claim {
+            amount: claim.amount.checked_add(amount)?,
+            release: claim.release.max(release),
+        },
+        None => {
+            let outstanding = CLAIMS
+                .prefix(address)
+                .keys(store, None, None, Order::Ascending)
+                .count();
+            if outstanding >= config.max_claims as usize {
+                return Err(ContractError::TooManyClaims{});
+            }
+            Claim {
+                amount,
+                release,
+            }
+        },
     };
-    claims.add(new_claim);
-    CLAIMS.save(store, address, &claims)
+    CLAIMS.save(store, key, &claim)?;
+    Ok(())
 
 }
 
@@ -153,11 +186,34 @@ pub fn get_partitioned_claims(
     now: Timestamp,
 ) -> StdResult<(Claims, Claims)> {
 
-    Ok( CLAIMS
-        .load(store, address.clone())?
-        .into_iter()
-        .partition(|c| c.is_released(now))
-    )
+    // bounded by the maximum number of claims per address
+    let claims = CLAIMS
+        .prefix(address)
+        .range(store, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<Claim>>>()?;
+    Ok(claims.into_iter().partition(|c| c.is_released(now)))
+
+}
+
+// a page of the claims of "address" ordered by release,
+// starting after the claim releasing at "start_after"
+pub fn get_claims_page(
+    store: &dyn Storage,
+    address: Addr,
+    start_after: Option<Timestamp>,
+    limit: usize,
+) -> StdResult<Vec<Claim>> {
+
+    let epoch = CONFIG.load(store)?.claim_epoch;
+    let start = start_after
+        .map(|release| Bound::exclusive(release.seconds() / epoch * epoch));
+    CLAIMS
+        .prefix(address)
+        .range(store, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
 
 }
 
@@ -169,12 +225,56 @@ pub fn remove_released_claims(
     now: Timestamp,
 ) -> StdResult<Uint128> {
 
-    let (released_claims, unreleased_claims) = get_partitioned_claims(store, address.clone(), now)?;
-    
-    // rewrite only unreleased claims
-    CLAIMS.save(store, address, &unreleased_claims.into())?;
-    
+    // buckets are ordered by release, so released
+    // claims are always at the start of the range
+    let released_claims = CLAIMS
+        .prefix(address.clone())
+        .range(store, None, None, Order::Ascending)
+        .take_while(|item| item.as_ref().map_or(true, |(_, c)| c.is_released(now)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut released = Uint128::zero();
+    for (bucket, claim) in released_claims {
+        CLAIMS.remove(store, (address.clone(), bucket));
+        released = released.checked_add(claim.amount)?;
+    }
+
     // return the amount of claims that have been released
-    released_claims.total()
+    Ok(released)
+
+}
+
+// remove "amount" from the claims of "address" that are still
+// locked as of "now", consuming the newest claims first.
+// returns the amount that was actually removed
+pub fn remove_locked_claims(
+    store: &mut dyn Storage,
+    address: Addr,
+    amount: Uint128,
+    now: Timestamp,
+) -> StdResult<Uint128> {
+
+    let locked_claims = CLAIMS
+        .prefix(address.clone())
+        .range(store, None, None, Order::Descending)
+        .take_while(|item| item.as_ref().map_or(true, |(_, c)| !c.is_released(now)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut remaining = amount;
+    for (bucket, mut claim) in locked_claims {
+        if remaining.is_zero() {
+            break;
+        }
+        let key = (address.clone(), bucket);
+        if claim.amount <= remaining {
+            remaining -= claim.amount;
+            CLAIMS.remove(store, key);
+        } else {
+            claim.amount -= remaining;
+            remaining = Uint128::zero();
+            CLAIMS.save(store, key, &claim)?;
+        }
+    }
+    Ok(amount.checked_sub(remaining)?)
 
 }
\ No newline at end of file